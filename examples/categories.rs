@@ -0,0 +1,39 @@
+use pshcalc::cat::SizedCategoryClassSet;
+use pshcalc::cursor;
+use pshcalc::set::Set;
+use std::time::Instant;
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let m = 5;
+
+    for morphisms in 1..=m {
+        println!(
+            "Counting categories with {} morphisms up to isomorphism...",
+            morphisms
+        );
+
+        let start = Instant::now();
+
+        let mut total = 0;
+        for objects in 1..=morphisms {
+            let class_set =
+                SizedCategoryClassSet::new(morphisms, Some(objects));
+
+            let mut count = 0;
+            cursor!(_ in &class_set => {
+                count += 1;
+            });
+            println!("  {} with {} objects", count, objects);
+            total += count;
+        }
+        let duration = start.elapsed();
+
+        println!(
+            "Found {} categories with {} morphisms up to isomorphism",
+            total, morphisms
+        );
+        println!("Time elapsed: {:.2?}", duration);
+    }
+
+    Ok(())
+}
@@ -1,10 +1,10 @@
-use pshcalc::cat::CategorySet;
+use pshcalc::cat::{CategoryClassSet, CategorySet};
 use pshcalc::cursor;
 use pshcalc::set::Set;
 use std::time::Instant;
 
 fn main() -> Result<(), Box<dyn std::error::Error>> {
-    let n = 4;
+    let n = 5;
 
     println!("Counting monoids with {} elements...", n);
 
@@ -25,5 +25,32 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     println!("Found {} monoids on {} elements", count, n);
     println!("Time elapsed: {:.2?}", duration);
 
+    let start = Instant::now();
+
+    let class_set = CategoryClassSet::new(1, vec![0; n - 1], vec![0; n - 1]);
+
+    let relabellings: usize = (1..n).product();
+    let mut classes = 0;
+    let mut rigid = 0;
+    let mut labelled = 0;
+    cursor!(monoid in &class_set => {
+        classes += 1;
+
+        let order = monoid.automorphisms().order();
+        if order == 1 {
+            rigid += 1;
+        }
+        labelled += relabellings / order;
+    });
+    let duration = start.elapsed();
+
+    println!(
+        "Found {} monoids on {} elements up to isomorphism",
+        classes, n
+    );
+    println!("  {} of them have no non-trivial automorphisms", rigid);
+    println!("  Orbit sizes add up to {} monoids", labelled);
+    println!("Time elapsed: {:.2?}", duration);
+
     Ok(())
 }
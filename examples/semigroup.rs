@@ -16,7 +16,7 @@ fn main() {
     let mut count = 0;
     let mut f = multiplications.allocate();
     traverse!(f in &multiplications => {
-        if is_associative(&f, &a, &a_x_a) {
+        if is_associative(f, &a, &a_x_a) {
             count += 1;
         }
     });
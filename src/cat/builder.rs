@@ -0,0 +1,548 @@
+use super::{Category, CategoryError, UNDEFINED};
+use std::collections::HashMap;
+
+#[derive(Debug, PartialEq)]
+pub enum CategoryBuilderError {
+    InvalidName { name: String },
+    DuplicateName { name: String },
+    UnknownObject { name: String },
+    UnknownMorphism { name: String },
+    MalformedEquation { equation: String },
+    IncompatibleComposition { g: String, f: String },
+    WrongComposite { g: String, f: String, h: String },
+    ConflictingComposition { g: String, f: String },
+    MissingComposition { g: String, f: String },
+    NonAssociative { morphisms: (String, String, String) },
+}
+
+impl std::fmt::Display for CategoryBuilderError {
+    fn fmt(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CategoryBuilderError::InvalidName { name } => {
+                write!(formatter, "Invalid name: {:?}", name)
+            }
+            CategoryBuilderError::DuplicateName { name } => {
+                write!(formatter, "Duplicate name: {}", name)
+            }
+            CategoryBuilderError::UnknownObject { name } => {
+                write!(formatter, "Unknown object: {}", name)
+            }
+            CategoryBuilderError::UnknownMorphism { name } => {
+                write!(formatter, "Unknown morphism: {}", name)
+            }
+            CategoryBuilderError::MalformedEquation { equation } => {
+                write!(formatter, "Malformed equation: {}", equation)
+            }
+            CategoryBuilderError::IncompatibleComposition { g, f } => {
+                write!(
+                    formatter,
+                    "Incompatible composition: g={} and f={}",
+                    g, f
+                )
+            }
+            CategoryBuilderError::WrongComposite { g, f, h } => {
+                write!(
+                    formatter,
+                    "Wrong composite: g={} and f={} cannot compose to h={}",
+                    g, f, h
+                )
+            }
+            CategoryBuilderError::ConflictingComposition { g, f } => {
+                write!(
+                    formatter,
+                    "Conflicting composition: g={} and f={}",
+                    g, f
+                )
+            }
+            CategoryBuilderError::MissingComposition { g, f } => {
+                write!(formatter, "Missing composition: g={} and f={}", g, f)
+            }
+            CategoryBuilderError::NonAssociative { morphisms } => {
+                write!(
+                    formatter,
+                    "Non-associative composition: {:?}",
+                    morphisms
+                )
+            }
+        }
+    }
+}
+
+impl std::error::Error for CategoryBuilderError {}
+
+// Whether a name can be read back out of a declaration or an equation: it
+// is not empty and contains no white space, none of `:`, `=`, `∘`, `·`, `→`
+// and `#`, and no `->`.
+pub(crate) fn is_valid_name(name: &str) -> bool {
+    !name.is_empty()
+        && !name.contains(|c: char| {
+            c.is_whitespace() || matches!(c, ':' | '=' | '∘' | '·' | '→' | '#')
+        })
+        && !name.contains("->")
+}
+
+// The names of the objects and morphisms of a built category, by index.
+// The identity on an object A is called id_A.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CategoryNames {
+    objects: Vec<String>,
+    morphisms: Vec<String>,
+    object_index: HashMap<String, usize>,
+    morphism_index: HashMap<String, usize>,
+}
+
+impl CategoryNames {
+    #[inline(always)]
+    fn new(objects: Vec<String>, morphisms: Vec<String>) -> Self {
+        let index = |names: &[String]| {
+            names
+                .iter()
+                .enumerate()
+                .map(|(i, name)| (name.clone(), i))
+                .collect()
+        };
+        CategoryNames {
+            object_index: index(&objects),
+            morphism_index: index(&morphisms),
+            objects,
+            morphisms,
+        }
+    }
+
+    // Names for a category without any: objects X0, X1, ... and the
+    // non-identity morphisms f followed by their index.
+    pub fn indexed(category: &Category) -> Self {
+        let n = category.number_of_objects();
+        let objects: Vec<String> =
+            (0..n).map(|object| format!("X{}", object)).collect();
+        let morphisms = objects
+            .iter()
+            .map(|object| format!("id_{}", object))
+            .chain(
+                (n..category.number_of_morphisms()).map(|f| format!("f{}", f)),
+            )
+            .collect();
+        CategoryNames::new(objects, morphisms)
+    }
+
+    #[inline(always)]
+    pub fn number_of_objects(&self) -> usize {
+        self.objects.len()
+    }
+
+    #[inline(always)]
+    pub fn number_of_morphisms(&self) -> usize {
+        self.morphisms.len()
+    }
+
+    #[inline(always)]
+    pub fn object_name(&self, object: usize) -> &str {
+        &self.objects[object]
+    }
+
+    #[inline(always)]
+    pub fn morphism_name(&self, morphism: usize) -> &str {
+        &self.morphisms[morphism]
+    }
+
+    #[inline(always)]
+    pub fn object(&self, name: &str) -> Option<usize> {
+        self.object_index.get(name).copied()
+    }
+
+    #[inline(always)]
+    pub fn morphism(&self, name: &str) -> Option<usize> {
+        self.morphism_index.get(name).copied()
+    }
+}
+
+#[derive(Clone, Debug)]
+enum Entry {
+    Object(String),
+    Morphism(String, String, String),
+    Equation(String),
+}
+
+// Collects named objects, named morphisms between them and equations
+// "g∘f = h", and turns them into a category. Names are declared before they
+// are used and have to be valid in the sense of `is_valid_name`.
+// Compositions with identities are filled in, so equations are only needed
+// for the composable pairs of non-identity morphisms, and every such pair
+// needs exactly one value. Objects are numbered in the order they are added
+// and so are morphisms, after the identities.
+#[derive(Clone, Debug, Default)]
+pub struct CategoryBuilder {
+    entries: Vec<Entry>,
+}
+
+impl CategoryBuilder {
+    #[inline(always)]
+    pub fn new() -> Self {
+        CategoryBuilder::default()
+    }
+
+    pub fn object(&mut self, name: &str) -> &mut Self {
+        self.entries.push(Entry::Object(name.to_string()));
+        self
+    }
+
+    // Declares a morphism `name`: `source` → `target`.
+    pub fn morphism(
+        &mut self,
+        name: &str,
+        source: &str,
+        target: &str,
+    ) -> &mut Self {
+        self.entries.push(Entry::Morphism(
+            name.to_string(),
+            source.to_string(),
+            target.to_string(),
+        ));
+        self
+    }
+
+    // Declares the morphisms in Hom(`source`, `target`) other than the
+    // identity.
+    pub fn hom(
+        &mut self,
+        source: &str,
+        target: &str,
+        names: &[&str],
+    ) -> &mut Self {
+        for name in names {
+            self.morphism(name, source, target);
+        }
+        self
+    }
+
+    // Adds an equation "g∘f = h".
+    pub fn equation(&mut self, equation: &str) -> &mut Self {
+        self.entries.push(Entry::Equation(equation.to_string()));
+        self
+    }
+
+    pub fn build(
+        &self,
+    ) -> Result<(Category, CategoryNames), CategoryBuilderError> {
+        self.build_located().map_err(|(_, error)| error)
+    }
+
+    // Like `build`, but also tells which entry an error is about, counting
+    // the calls to `object`, `morphism` and `equation` from 0; errors about
+    // the table as a whole come with None.
+    pub(crate) fn build_located(
+        &self,
+    ) -> Result<(Category, CategoryNames), (Option<usize>, CategoryBuilderError)>
+    {
+        let mut resolution = Resolution::new(&self.entries);
+        for (index, entry) in self.entries.iter().enumerate() {
+            resolution
+                .add(entry)
+                .map_err(|error| (Some(index), error))?;
+        }
+        let Resolution {
+            category,
+            objects,
+            morphisms,
+            ..
+        } = resolution;
+        let names = CategoryNames::new(objects, morphisms);
+        let n = category.number_of_objects();
+        let m = category.number_of_morphisms();
+
+        for g in n..m {
+            for f in n..m {
+                if category.target(f) == category.source(g)
+                    && category.composition(g, f) == Some(UNDEFINED)
+                {
+                    return Err((
+                        None,
+                        CategoryBuilderError::MissingComposition {
+                            g: names.morphisms[g].clone(),
+                            f: names.morphisms[f].clone(),
+                        },
+                    ));
+                }
+            }
+        }
+
+        // the table is well-defined by now, so only associativity can fail
+        if let Err(CategoryError::NonAssociative {
+            morphisms: (h, g, f),
+        }) = category.validate_associativity()
+        {
+            return Err((
+                None,
+                CategoryBuilderError::NonAssociative {
+                    morphisms: (
+                        names.morphisms[h].clone(),
+                        names.morphisms[g].clone(),
+                        names.morphisms[f].clone(),
+                    ),
+                },
+            ));
+        }
+        Ok((category, names))
+    }
+}
+
+// The category as far as the entries read so far give it, with UNDEFINED
+// for the compositions that are still missing.
+struct Resolution {
+    category: Category,
+    objects: Vec<String>,
+    morphisms: Vec<String>,
+    object_index: HashMap<String, usize>,
+    morphism_index: HashMap<String, usize>,
+    declared: usize,
+}
+
+impl Resolution {
+    fn new(entries: &[Entry]) -> Self {
+        let n = entries
+            .iter()
+            .filter(|entry| matches!(entry, Entry::Object(_)))
+            .count();
+        let k = entries
+            .iter()
+            .filter(|entry| matches!(entry, Entry::Morphism(..)))
+            .count();
+        Resolution {
+            category: Category::new(
+                n,
+                vec![0; k],
+                vec![0; k],
+                vec![UNDEFINED; k * k],
+            ),
+            objects: Vec::with_capacity(n),
+            morphisms: vec![String::new(); n + k],
+            object_index: HashMap::new(),
+            morphism_index: HashMap::new(),
+            declared: 0,
+        }
+    }
+
+    fn add(&mut self, entry: &Entry) -> Result<(), CategoryBuilderError> {
+        match entry {
+            Entry::Object(name) => {
+                let object = self.objects.len();
+                let identity = format!("id_{}", name);
+                self.check_name(name, self.object_index.contains_key(name))?;
+                self.check_name(
+                    &identity,
+                    self.morphism_index.contains_key(&identity),
+                )?;
+                self.object_index.insert(name.clone(), object);
+                self.morphism_index.insert(identity.clone(), object);
+                self.objects.push(name.clone());
+                self.morphisms[object] = identity;
+            }
+            Entry::Morphism(name, source, target) => {
+                let i = self.declared;
+                let f = self.category.number_of_objects() + i;
+                self.check_name(name, self.morphism_index.contains_key(name))?;
+                self.category.source[i] = self.object(source)?;
+                self.category.target[i] = self.object(target)?;
+                self.morphism_index.insert(name.clone(), f);
+                self.morphisms[f] = name.clone();
+                self.declared += 1;
+            }
+            Entry::Equation(equation) => self.equation(equation)?,
+        }
+        Ok(())
+    }
+
+    #[inline(always)]
+    fn check_name(
+        &self,
+        name: &str,
+        taken: bool,
+    ) -> Result<(), CategoryBuilderError> {
+        if !is_valid_name(name) {
+            return Err(CategoryBuilderError::InvalidName {
+                name: name.to_string(),
+            });
+        }
+        if taken {
+            return Err(CategoryBuilderError::DuplicateName {
+                name: name.to_string(),
+            });
+        }
+        Ok(())
+    }
+
+    #[inline(always)]
+    fn object(&self, name: &str) -> Result<usize, CategoryBuilderError> {
+        self.object_index.get(name).copied().ok_or_else(|| {
+            CategoryBuilderError::UnknownObject {
+                name: name.to_string(),
+            }
+        })
+    }
+
+    #[inline(always)]
+    fn morphism(&self, name: &str) -> Result<usize, CategoryBuilderError> {
+        self.morphism_index.get(name).copied().ok_or_else(|| {
+            CategoryBuilderError::UnknownMorphism {
+                name: name.to_string(),
+            }
+        })
+    }
+
+    // Reads "g∘f = h" and sets g∘f to h.
+    fn equation(&mut self, equation: &str) -> Result<(), CategoryBuilderError> {
+        let malformed = || CategoryBuilderError::MalformedEquation {
+            equation: equation.to_string(),
+        };
+        let (left, h) = equation.split_once('=').ok_or_else(malformed)?;
+        let (g, f) = left.split_once('∘').ok_or_else(malformed)?;
+        let parts = [g.trim(), f.trim(), h.trim()];
+        if parts
+            .iter()
+            .any(|part| part.is_empty() || part.contains(['=', '∘']))
+        {
+            return Err(malformed());
+        }
+        let g = self.morphism(parts[0])?;
+        let f = self.morphism(parts[1])?;
+        let h = self.morphism(parts[2])?;
+
+        let category = &mut self.category;
+        let names = &self.morphisms;
+        let named = |g: usize, f: usize| (names[g].clone(), names[f].clone());
+        if category.target(f) != category.source(g) {
+            let (g, f) = named(g, f);
+            return Err(CategoryBuilderError::IncompatibleComposition { g, f });
+        }
+        if category.source(h) != category.source(f)
+            || category.target(h) != category.target(g)
+        {
+            let (g, f) = named(g, f);
+            let h = names[h].clone();
+            return Err(CategoryBuilderError::WrongComposite { g, f, h });
+        }
+        let n = category.number_of_objects();
+        let k = category.number_of_morphisms() - n;
+        let conflicting = if g < n || f < n {
+            // identities compose as they must
+            category.composition(g, f) != Some(h)
+        } else {
+            let entry = &mut category.composition[(g - n) * k + (f - n)];
+            if *entry == UNDEFINED {
+                *entry = h;
+            }
+            *entry != h
+        };
+        if conflicting {
+            let (g, f) = named(g, f);
+            return Err(CategoryBuilderError::ConflictingComposition { g, f });
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // An isomorphism f: A → B with inverse g.
+    fn isomorphism() -> CategoryBuilder {
+        let mut builder = CategoryBuilder::new();
+        builder
+            .object("A")
+            .object("B")
+            .hom("A", "B", &["f"])
+            .hom("B", "A", &["g"])
+            .equation("g∘f = id_A")
+            .equation("f∘g = id_B");
+        builder
+    }
+
+    fn monoid(equations: &[&str]) -> CategoryBuilder {
+        let mut builder = CategoryBuilder::new();
+        builder.object("M").hom("M", "M", &["a", "b"]);
+        for equation in equations {
+            builder.equation(equation);
+        }
+        builder
+    }
+
+    fn name(name: &str) -> String {
+        name.to_string()
+    }
+
+    #[test]
+    fn builds_from_homs_and_equations() {
+        let (category, names) = isomorphism().build().unwrap();
+        assert!(category.validate().is_ok());
+        assert_eq!(names.object("B"), Some(1));
+        assert_eq!(names.morphism("id_A"), Some(0));
+        assert_eq!(names.morphism("g"), Some(3));
+        assert_eq!(names.morphism_name(2), "f");
+        // the identities compose without equations
+        assert_eq!(category.composition(2, 0), Some(2));
+        assert_eq!(category.composition(1, 2), Some(2));
+        assert_eq!(category.composition(3, 2), Some(0));
+        let expected = Category::new(
+            2,
+            vec![0, 1],
+            vec![1, 0],
+            vec![UNDEFINED, 1, 0, UNDEFINED],
+        );
+        assert_eq!(category, expected);
+    }
+
+    #[test]
+    fn reports_a_missing_composition() {
+        let mut builder = CategoryBuilder::new();
+        builder
+            .object("A")
+            .object("B")
+            .hom("A", "B", &["f"])
+            .hom("B", "A", &["g"])
+            .equation("g∘f = id_A");
+        assert_eq!(
+            builder.build().unwrap_err(),
+            CategoryBuilderError::MissingComposition {
+                g: name("f"),
+                f: name("g")
+            }
+        );
+    }
+
+    #[test]
+    fn reports_a_wrong_composite() {
+        let mut builder = isomorphism();
+        builder.equation("g∘f = f");
+        assert_eq!(
+            builder.build().unwrap_err(),
+            CategoryBuilderError::WrongComposite {
+                g: name("g"),
+                f: name("f"),
+                h: name("f")
+            }
+        );
+    }
+
+    #[test]
+    fn reports_a_conflicting_composition() {
+        let builder = monoid(&["a∘a = a", "a∘a = b"]);
+        assert_eq!(
+            builder.build().unwrap_err(),
+            CategoryBuilderError::ConflictingComposition {
+                g: name("a"),
+                f: name("a")
+            }
+        );
+    }
+
+    #[test]
+    fn reports_a_non_associative_table() {
+        // (a∘a)∘b = b but a∘(a∘b) = id_M
+        let builder = monoid(&["a∘a = id_M", "a∘b = a", "b∘a = a", "b∘b = b"]);
+        assert!(matches!(
+            builder.build().unwrap_err(),
+            CategoryBuilderError::NonAssociative { .. }
+        ));
+    }
+}
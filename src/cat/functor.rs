@@ -0,0 +1,323 @@
+use super::Category;
+use crate::set::{Backtrack, Set, Variable};
+
+#[derive(Debug, PartialEq)]
+pub enum FunctorError {
+    NotIdentityPreserving { object: usize },
+    NotWellDefined { f: usize },
+    NotCompositionPreserving { g: usize, f: usize },
+}
+
+impl std::fmt::Display for FunctorError {
+    fn fmt(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            FunctorError::NotIdentityPreserving { object } => {
+                write!(formatter, "Identity not preserved: object={}", object)
+            }
+            FunctorError::NotWellDefined { f } => {
+                write!(formatter, "Not well-defined: f={}", f)
+            }
+            FunctorError::NotCompositionPreserving { g, f } => {
+                write!(
+                    formatter,
+                    "Composition not preserved: g={} and f={}",
+                    g, f
+                )
+            }
+        }
+    }
+}
+
+impl std::error::Error for FunctorError {}
+
+// A functor between two categories, stored as the image of every object
+// and of every morphism (identities included).
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Functor {
+    objects: Vec<usize>,
+    morphisms: Vec<usize>,
+}
+
+impl Functor {
+    #[inline(always)]
+    pub fn new(objects: Vec<usize>, morphisms: Vec<usize>) -> Self {
+        Functor { objects, morphisms }
+    }
+
+    #[inline(always)]
+    pub fn allocate(domain: &Category) -> Variable<Self> {
+        Variable::uninitialized(Functor {
+            objects: vec![0; domain.number_of_objects()],
+            morphisms: vec![0; domain.number_of_morphisms()],
+        })
+    }
+
+    #[inline(always)]
+    pub fn object(&self, object: usize) -> usize {
+        self.objects[object]
+    }
+
+    #[inline(always)]
+    pub fn morphism(&self, morphism: usize) -> usize {
+        self.morphisms[morphism]
+    }
+
+    #[inline(always)]
+    pub fn validate(
+        &self,
+        domain: &Category,
+        codomain: &Category,
+    ) -> Result<(), FunctorError> {
+        self.validate_identities(domain)?;
+        self.validate_well_definedness(domain, codomain)?;
+        self.validate_composition(domain, codomain)?;
+        Ok(())
+    }
+
+    #[inline(always)]
+    fn validate_identities(
+        &self,
+        domain: &Category,
+    ) -> Result<(), FunctorError> {
+        for object in 0..domain.number_of_objects() {
+            if self.morphism(object) != self.object(object) {
+                return Err(FunctorError::NotIdentityPreserving { object });
+            }
+        }
+        Ok(())
+    }
+
+    #[inline(always)]
+    fn validate_well_definedness(
+        &self,
+        domain: &Category,
+        codomain: &Category,
+    ) -> Result<(), FunctorError> {
+        for f in 0..domain.number_of_morphisms() {
+            let image = self.morphism(f);
+            if codomain.source(image) != self.object(domain.source(f))
+                || codomain.target(image) != self.object(domain.target(f))
+            {
+                return Err(FunctorError::NotWellDefined { f });
+            }
+        }
+        Ok(())
+    }
+
+    #[inline(always)]
+    fn validate_composition(
+        &self,
+        domain: &Category,
+        codomain: &Category,
+    ) -> Result<(), FunctorError> {
+        for g in 0..domain.number_of_morphisms() {
+            for f in 0..domain.number_of_morphisms() {
+                let Some(gf) = domain.composition(g, f) else {
+                    continue;
+                };
+                let left = Some(self.morphism(gf));
+                let right =
+                    codomain.composition(self.morphism(g), self.morphism(f));
+                if left != right {
+                    return Err(FunctorError::NotCompositionPreserving {
+                        g,
+                        f,
+                    });
+                }
+            }
+        }
+        Ok(())
+    }
+
+    // Checks a partially assigned functor after the image of the
+    // non-identity morphism `last` has been chosen, assuming all morphisms
+    // before it were already checked.
+    #[inline(always)]
+    fn validate_morphism(
+        &self,
+        domain: &Category,
+        codomain: &Category,
+        last: usize,
+    ) -> Result<(), FunctorError> {
+        let image = self.morphism(last);
+        if codomain.source(image) != self.object(domain.source(last))
+            || codomain.target(image) != self.object(domain.target(last))
+        {
+            return Err(FunctorError::NotWellDefined { f: last });
+        }
+        for g in 0..=last {
+            for f in 0..=last {
+                let Some(gf) = domain.composition(g, f) else {
+                    continue;
+                };
+                if gf > last || (g != last && f != last && gf != last) {
+                    continue;
+                }
+                let right =
+                    codomain.composition(self.morphism(g), self.morphism(f));
+                if Some(self.morphism(gf)) != right {
+                    return Err(FunctorError::NotCompositionPreserving {
+                        g,
+                        f,
+                    });
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+// Enumerates all functors from `domain` to `codomain`. The object map is
+// chosen first, after which the images of the non-identity morphisms are
+// chosen one at a time, abandoning partial assignments that already fail to
+// preserve endpoints or composition.
+#[derive(Clone, Debug)]
+pub struct FunctorSet<'a> {
+    domain: &'a Category,
+    codomain: &'a Category,
+}
+
+impl<'a> FunctorSet<'a> {
+    #[inline(always)]
+    pub fn new(domain: &'a Category, codomain: &'a Category) -> Self {
+        FunctorSet { domain, codomain }
+    }
+}
+
+// Slots are the objects of the domain followed by its non-identity
+// morphisms, which conveniently share their numbering.
+impl Backtrack<Functor> for FunctorSet<'_> {
+    #[inline(always)]
+    fn slots(&self, _: &Functor) -> usize {
+        self.domain.number_of_morphisms()
+    }
+
+    #[inline(always)]
+    fn candidates(&self, _: &Functor, slot: usize) -> usize {
+        if slot < self.domain.number_of_objects() {
+            self.codomain.number_of_objects()
+        } else {
+            self.codomain.number_of_morphisms()
+        }
+    }
+
+    #[inline(always)]
+    fn assign(&self, current: &mut Functor, slot: usize, value: usize) -> bool {
+        if slot < self.domain.number_of_objects() {
+            current.objects[slot] = value;
+            current.morphisms[slot] = value;
+            return true;
+        }
+        current.morphisms[slot] = value;
+        current
+            .validate_morphism(self.domain, self.codomain, slot)
+            .is_ok()
+    }
+
+    #[inline(always)]
+    fn candidate(&self, current: &Functor, slot: usize) -> usize {
+        current.morphisms[slot]
+    }
+}
+
+impl Set<Functor> for FunctorSet<'_> {
+    #[inline(always)]
+    fn allocate(&self) -> Variable<Functor> {
+        Functor::allocate(self.domain)
+    }
+
+    #[inline(always)]
+    fn next(&self, current: &mut Functor) -> bool {
+        self.search_next(current)
+    }
+
+    #[inline(always)]
+    fn reset(&self, current: &mut Functor) -> bool {
+        self.search_first(current)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cat::{SizedCategoryClassSet, UNDEFINED};
+    use crate::cursor;
+    use crate::set::HomSet;
+
+    fn count(domain: &Category, codomain: &Category) -> usize {
+        let mut count = 0;
+        cursor!(functor in &FunctorSet::new(domain, codomain) => {
+            assert!(functor.validate(domain, codomain).is_ok());
+            count += 1;
+        });
+        count
+    }
+
+    // Tries every map on the morphisms that extends to the objects.
+    fn brute_force_count(domain: &Category, codomain: &Category) -> usize {
+        let maps = HomSet::new(&domain.morphisms(), &codomain.morphisms());
+        let mut count = 0;
+        cursor!(morphisms in &maps => {
+            let objects = morphisms[..domain.number_of_objects()].to_vec();
+            if objects.iter().all(|&x| x < codomain.number_of_objects()) {
+                let functor = Functor::new(objects, morphisms.clone());
+                if functor.validate(domain, codomain).is_ok() {
+                    count += 1;
+                }
+            }
+        });
+        count
+    }
+
+    #[test]
+    fn counts_monoid_homomorphisms() {
+        let group = Category::new(1, vec![0], vec![0], vec![0]);
+        let semilattice = Category::new(1, vec![0], vec![0], vec![1]);
+        assert_eq!(count(&group, &group), 2);
+        assert_eq!(count(&group, &semilattice), 1);
+        assert_eq!(count(&semilattice, &group), 1);
+        assert_eq!(count(&semilattice, &semilattice), 2);
+    }
+
+    #[test]
+    fn agrees_with_brute_force() {
+        let mut categories = Vec::new();
+        for m in 1..=3 {
+            cursor!((_, category) in &SizedCategoryClassSet::new(m, None) => {
+                categories.push(category.clone());
+            });
+        }
+        for domain in &categories {
+            for codomain in &categories {
+                assert_eq!(
+                    count(domain, codomain),
+                    brute_force_count(domain, codomain)
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn reports_each_error() {
+        let group = Category::new(1, vec![0], vec![0], vec![0]);
+        let semilattice = Category::new(1, vec![0], vec![0], vec![1]);
+        // 0 → 1
+        let arrow = Category::new(2, vec![0], vec![1], vec![UNDEFINED]);
+
+        let functor = Functor::new(vec![0, 1], vec![1, 0, 2]);
+        assert_eq!(
+            functor.validate(&arrow, &arrow),
+            Err(FunctorError::NotIdentityPreserving { object: 0 })
+        );
+        let functor = Functor::new(vec![1, 0], vec![1, 0, 2]);
+        assert_eq!(
+            functor.validate(&arrow, &arrow),
+            Err(FunctorError::NotWellDefined { f: 2 })
+        );
+        let functor = Functor::new(vec![0], vec![0, 1]);
+        assert_eq!(
+            functor.validate(&group, &semilattice),
+            Err(FunctorError::NotCompositionPreserving { g: 1, f: 1 })
+        );
+    }
+}
@@ -0,0 +1,642 @@
+use super::{Category, CategorySet, UNDEFINED};
+use crate::set::{Filter, Set, Variable};
+
+// A pair of bijections, one on the objects and one on the morphisms, that
+// sends identities to identities.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Relabelling {
+    objects: Vec<usize>,
+    morphisms: Vec<usize>,
+}
+
+impl Relabelling {
+    #[inline(always)]
+    pub fn new(objects: Vec<usize>, morphisms: Vec<usize>) -> Self {
+        Relabelling { objects, morphisms }
+    }
+
+    #[inline(always)]
+    pub fn identity(
+        number_of_objects: usize,
+        number_of_morphisms: usize,
+    ) -> Self {
+        Relabelling {
+            objects: (0..number_of_objects).collect(),
+            morphisms: (0..number_of_morphisms).collect(),
+        }
+    }
+
+    #[inline(always)]
+    pub fn object(&self, object: usize) -> usize {
+        self.objects[object]
+    }
+
+    #[inline(always)]
+    pub fn morphism(&self, morphism: usize) -> usize {
+        self.morphisms[morphism]
+    }
+
+    #[inline(always)]
+    pub fn objects(&self) -> &[usize] {
+        &self.objects
+    }
+
+    #[inline(always)]
+    pub fn morphisms(&self) -> &[usize] {
+        &self.morphisms
+    }
+
+    #[inline(always)]
+    pub fn inverse(&self) -> Self {
+        let mut objects = vec![0; self.objects.len()];
+        for (x, &y) in self.objects.iter().enumerate() {
+            objects[y] = x;
+        }
+        let mut morphisms = vec![0; self.morphisms.len()];
+        for (f, &g) in self.morphisms.iter().enumerate() {
+            morphisms[g] = f;
+        }
+        Relabelling { objects, morphisms }
+    }
+
+    // The relabelling that applies `self` first and `other` second.
+    #[inline(always)]
+    pub fn then(&self, other: &Relabelling) -> Self {
+        Relabelling {
+            objects: self.objects.iter().map(|&x| other.object(x)).collect(),
+            morphisms: self
+                .morphisms
+                .iter()
+                .map(|&f| other.morphism(f))
+                .collect(),
+        }
+    }
+}
+
+impl Category {
+    // The category whose object `relabelling.object(x)` and morphism
+    // `relabelling.morphism(f)` play the roles of `x` and `f` in `self`.
+    pub fn relabel(&self, relabelling: &Relabelling) -> Category {
+        let n = self.number_of_objects();
+        let m = self.number_of_morphisms();
+        let k = m - n;
+        let mut source = vec![0; k];
+        let mut target = vec![0; k];
+        let mut composition = vec![UNDEFINED; k * k];
+        for f in n..m {
+            let image = relabelling.morphism(f) - n;
+            source[image] = relabelling.object(self.source(f));
+            target[image] = relabelling.object(self.target(f));
+        }
+        for g in n..m {
+            for f in n..m {
+                let Some(gf) = self.composition(g, f) else {
+                    continue;
+                };
+                let j = relabelling.morphism(g) - n;
+                let i = relabelling.morphism(f) - n;
+                composition[j * k + i] = relabelling.morphism(gf);
+            }
+        }
+        Category::new(n, source, target, composition)
+    }
+
+    // The relabelling that sends `self` to its canonical form: among all
+    // relabellings of `self`, the one that is lexicographically smallest in
+    // source, then target, then composition table.
+    pub fn canonical_relabelling(&self) -> Relabelling {
+        let n = self.number_of_objects();
+        let m = self.number_of_morphisms();
+        let mut best: Option<(Category, Relabelling)> = None;
+        for objects in permutations(n) {
+            // The smallest quiver for this object map lists the non-identity
+            // morphisms sorted by source and target; it is unique up to
+            // permutations within the hom-sets.
+            let mut order: Vec<usize> = (n..m).collect();
+            order.sort_by_key(|&f| {
+                (objects[self.source(f)], objects[self.target(f)])
+            });
+            let mut morphisms = objects.clone();
+            morphisms.resize(m, 0);
+            for (position, &f) in order.iter().enumerate() {
+                morphisms[f] = n + position;
+            }
+            let sorted = Relabelling::new(objects, morphisms);
+            let quiver = self.relabel(&sorted);
+            if let Some((category, _)) = &best {
+                if (&quiver.source, &quiver.target)
+                    > (&category.source, &category.target)
+                {
+                    continue;
+                }
+            }
+
+            let identity: Vec<usize> = (0..n).collect();
+            for symmetry in morphism_relabellings(
+                n,
+                &quiver.source,
+                &quiver.target,
+                &identity,
+            ) {
+                let relabelling = sorted.then(&symmetry);
+                let candidate = self.relabel(&relabelling);
+                let smaller = match &best {
+                    Some((category, _)) => candidate.key() < category.key(),
+                    None => true,
+                };
+                if smaller {
+                    best = Some((candidate, relabelling));
+                }
+            }
+        }
+        best.map(|(_, relabelling)| relabelling)
+            .unwrap_or_else(|| Relabelling::identity(n, m))
+    }
+
+    // A representative of the isomorphism class of `self`: two categories
+    // are isomorphic exactly when their canonical forms are equal.
+    pub fn canonical_form(&self) -> Category {
+        self.relabel(&self.canonical_relabelling())
+    }
+
+    // A relabelling that turns `self` into `other`, if they are isomorphic.
+    pub fn isomorphism(&self, other: &Category) -> Option<Relabelling> {
+        if self.number_of_objects() != other.number_of_objects()
+            || self.number_of_morphisms() != other.number_of_morphisms()
+        {
+            return None;
+        }
+        let to_canonical = self.canonical_relabelling();
+        let from_canonical = other.canonical_relabelling();
+        if self.relabel(&to_canonical) != other.relabel(&from_canonical) {
+            return None;
+        }
+        Some(to_canonical.then(&from_canonical.inverse()))
+    }
+
+    #[inline(always)]
+    pub fn is_isomorphic(&self, other: &Category) -> bool {
+        self.isomorphism(other).is_some()
+    }
+
+    #[inline(always)]
+    fn key(&self) -> (&[usize], &[usize], &[usize]) {
+        (&self.source, &self.target, &self.composition)
+    }
+}
+
+// The automorphisms of a category, each given as the relabelling that maps
+// the category onto itself.
+#[derive(Clone, Debug)]
+pub struct AutomorphismGroup {
+    elements: Vec<Relabelling>,
+}
+
+impl AutomorphismGroup {
+    #[inline(always)]
+    pub fn order(&self) -> usize {
+        self.elements.len()
+    }
+
+    #[inline(always)]
+    pub fn elements(&self) -> &[Relabelling] {
+        &self.elements
+    }
+}
+
+impl Category {
+    pub fn automorphisms(&self) -> AutomorphismGroup {
+        let mut elements = Vec::new();
+        for objects in permutations(self.number_of_objects()) {
+            let mut morphisms = objects.clone();
+            let mut taken = vec![false; self.number_of_morphisms()];
+            self.extend_automorphisms(
+                &objects,
+                &mut morphisms,
+                &mut taken,
+                &mut elements,
+            );
+        }
+        AutomorphismGroup { elements }
+    }
+
+    fn extend_automorphisms(
+        &self,
+        objects: &[usize],
+        morphisms: &mut Vec<usize>,
+        taken: &mut Vec<bool>,
+        elements: &mut Vec<Relabelling>,
+    ) {
+        let f = morphisms.len();
+        if f == self.number_of_morphisms() {
+            elements
+                .push(Relabelling::new(objects.to_vec(), morphisms.clone()));
+            return;
+        }
+        for image in self.number_of_objects()..self.number_of_morphisms() {
+            if taken[image]
+                || self.source(image) != objects[self.source(f)]
+                || self.target(image) != objects[self.target(f)]
+            {
+                continue;
+            }
+            morphisms.push(image);
+            if self.preserves_composition(morphisms) {
+                taken[image] = true;
+                self.extend_automorphisms(objects, morphisms, taken, elements);
+                taken[image] = false;
+            }
+            morphisms.pop();
+        }
+    }
+
+    // Whether a partial map on the morphisms, whose last entry was just
+    // assigned, respects all compositions among the assigned morphisms that
+    // involve the last one.
+    #[inline(always)]
+    fn preserves_composition(&self, morphisms: &[usize]) -> bool {
+        let last = morphisms.len() - 1;
+        for g in 0..morphisms.len() {
+            for f in 0..morphisms.len() {
+                let Some(gf) = self.composition(g, f) else {
+                    continue;
+                };
+                if gf > last || (g != last && f != last && gf != last) {
+                    continue;
+                }
+                let image = self.composition(morphisms[g], morphisms[f]);
+                if image != Some(morphisms[gf]) {
+                    return false;
+                }
+            }
+        }
+        true
+    }
+}
+
+// All permutations of 0..n.
+pub(crate) fn permutations(n: usize) -> Vec<Vec<usize>> {
+    let mut permutations = Vec::new();
+    let mut permutation = Vec::with_capacity(n);
+    let mut used = vec![false; n];
+    extend_permutations(n, &mut permutation, &mut used, &mut permutations);
+    permutations
+}
+
+fn extend_permutations(
+    n: usize,
+    permutation: &mut Vec<usize>,
+    used: &mut Vec<bool>,
+    permutations: &mut Vec<Vec<usize>>,
+) {
+    if permutation.len() == n {
+        permutations.push(permutation.clone());
+        return;
+    }
+    for i in 0..n {
+        if used[i] {
+            continue;
+        }
+        used[i] = true;
+        permutation.push(i);
+        extend_permutations(n, permutation, used, permutations);
+        permutation.pop();
+        used[i] = false;
+    }
+}
+
+// All relabellings of the quiver given by `source` and `target` (on the
+// non-identity morphisms) onto itself.
+pub(crate) fn quiver_automorphisms(
+    number_of_objects: usize,
+    source: &[usize],
+    target: &[usize],
+) -> Vec<Relabelling> {
+    let mut automorphisms = Vec::new();
+    for objects in permutations(number_of_objects) {
+        automorphisms.extend(morphism_relabellings(
+            number_of_objects,
+            source,
+            target,
+            &objects,
+        ));
+    }
+    automorphisms
+}
+
+// All relabellings of the quiver onto itself that act on the objects as
+// given by `objects`.
+fn morphism_relabellings(
+    number_of_objects: usize,
+    source: &[usize],
+    target: &[usize],
+    objects: &[usize],
+) -> Vec<Relabelling> {
+    let mut relabellings = Vec::new();
+    let mut morphisms = objects.to_vec();
+    let mut taken = vec![false; source.len()];
+    extend_morphism_maps(
+        number_of_objects,
+        source,
+        target,
+        objects,
+        &mut morphisms,
+        &mut taken,
+        &mut relabellings,
+    );
+    relabellings
+}
+
+fn extend_morphism_maps(
+    number_of_objects: usize,
+    source: &[usize],
+    target: &[usize],
+    objects: &[usize],
+    morphisms: &mut Vec<usize>,
+    taken: &mut Vec<bool>,
+    automorphisms: &mut Vec<Relabelling>,
+) {
+    let f = morphisms.len() - number_of_objects;
+    if f == source.len() {
+        automorphisms
+            .push(Relabelling::new(objects.to_vec(), morphisms.clone()));
+        return;
+    }
+    for g in 0..source.len() {
+        if taken[g]
+            || source[g] != objects[source[f]]
+            || target[g] != objects[target[f]]
+        {
+            continue;
+        }
+        taken[g] = true;
+        morphisms.push(g + number_of_objects);
+        extend_morphism_maps(
+            number_of_objects,
+            source,
+            target,
+            objects,
+            morphisms,
+            taken,
+            automorphisms,
+        );
+        morphisms.pop();
+        taken[g] = false;
+    }
+}
+
+// Enumerates the categories with the given objects and morphisms up to
+// isomorphism. Two such categories are isomorphic exactly when a relabelling
+// of the underlying quiver turns one composition table into the other, so of
+// every orbit only the table that is lexicographically smallest among its
+// relabellings is kept.
+#[derive(Clone)]
+pub struct CategoryClassSet {
+    categories: CategorySet,
+    symmetries: Vec<(Relabelling, Relabelling)>,
+}
+
+impl CategoryClassSet {
+    #[inline(always)]
+    pub fn new(
+        number_of_objects: usize,
+        source: Vec<usize>,
+        target: Vec<usize>,
+    ) -> Self {
+        let symmetries =
+            quiver_automorphisms(number_of_objects, &source, &target)
+                .into_iter()
+                .map(|relabelling| {
+                    let inverse = relabelling.inverse();
+                    (relabelling, inverse)
+                })
+                .collect();
+        let categories = CategorySet::new(number_of_objects, source, target);
+        CategoryClassSet {
+            categories,
+            symmetries,
+        }
+    }
+
+    // Whether no relabelling of the quiver yields a smaller table.
+    #[inline(always)]
+    fn is_canonical(&self, category: &Category) -> bool {
+        let n = category.number_of_objects();
+        let m = category.number_of_morphisms();
+        for (relabelling, inverse) in &self.symmetries {
+            'cells: for g in n..m {
+                for f in n..m {
+                    // incomposable pairs are sent to incomposable pairs
+                    let Some(original) = category.composition(g, f) else {
+                        continue;
+                    };
+                    let relabelled = category
+                        .composition(inverse.morphism(g), inverse.morphism(f))
+                        .map(|gf| relabelling.morphism(gf))
+                        .expect("relabellings preserve composability");
+                    if relabelled < original {
+                        return false;
+                    }
+                    if relabelled > original {
+                        break 'cells;
+                    }
+                }
+            }
+        }
+        true
+    }
+}
+
+impl Filter<Category> for CategoryClassSet {
+    #[inline(always)]
+    fn inner_reset(&self, current: &mut Category) -> bool {
+        self.categories.reset(current)
+    }
+
+    #[inline(always)]
+    fn inner_next(&self, current: &mut Category) -> bool {
+        self.categories.next(current)
+    }
+
+    #[inline(always)]
+    fn accepts(&self, current: &Category) -> bool {
+        self.is_canonical(current)
+    }
+}
+
+impl Set<Category> for CategoryClassSet {
+    #[inline(always)]
+    fn allocate(&self) -> Variable<Category> {
+        self.categories.allocate()
+    }
+
+    #[inline(always)]
+    fn next(&self, current: &mut Category) -> bool {
+        self.filter_next(current)
+    }
+
+    #[inline(always)]
+    fn reset(&self, current: &mut Category) -> bool {
+        self.filter_first(current)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cat::{quivers, SizedCategoryClassSet};
+    use crate::cursor;
+
+    fn count<T>(set: &impl Set<T>) -> usize {
+        let mut count = 0;
+        cursor!(_ in set => {
+            count += 1;
+        });
+        count
+    }
+
+    fn collect(set: &impl Set<Category>) -> Vec<Category> {
+        let mut categories = Vec::new();
+        cursor!(category in set => {
+            categories.push(category.clone());
+        });
+        categories
+    }
+
+    // All categories with m morphisms on the quivers up to relabelling.
+    fn labelled(m: usize) -> Vec<Category> {
+        let mut categories = Vec::new();
+        for n in 1..=m {
+            for (source, target) in quivers(n, m - n) {
+                categories
+                    .extend(collect(&CategorySet::new(n, source, target)));
+            }
+        }
+        categories
+    }
+
+    fn monoids(n: usize) -> CategoryClassSet {
+        CategoryClassSet::new(1, vec![0; n - 1], vec![0; n - 1])
+    }
+
+    // OEIS A058129
+    #[test]
+    fn counts_monoids_up_to_isomorphism() {
+        let counts: Vec<usize> = (1..=5).map(|n| count(&monoids(n))).collect();
+        assert_eq!(counts, [1, 2, 7, 35, 228]);
+    }
+
+    #[test]
+    fn counts_categories_with_several_objects() {
+        // two parallel arrows 0 → 1, which cannot be composed
+        assert_eq!(count(&CategoryClassSet::new(2, vec![0, 0], vec![1, 1])), 1);
+        // an arrow 0 → 1 followed by an endomorphism of 1
+        assert_eq!(count(&CategoryClassSet::new(2, vec![0, 1], vec![1, 1])), 2);
+    }
+
+    #[test]
+    fn isomorphism_reaches_the_canonical_form() {
+        for m in 1..=4 {
+            for category in labelled(m) {
+                let canonical = category.canonical_form();
+                let isomorphism = category.isomorphism(&canonical).unwrap();
+                assert_eq!(category.relabel(&isomorphism), canonical);
+            }
+        }
+    }
+
+    #[test]
+    fn isomorphism_agrees_with_classes() {
+        for m in 1..=4 {
+            let mut classes = Vec::new();
+            cursor!((_, category) in &SizedCategoryClassSet::new(m, None) => {
+                classes.push(category.clone());
+            });
+            for (i, left) in classes.iter().enumerate() {
+                for (j, right) in classes.iter().enumerate() {
+                    assert_eq!(left.is_isomorphic(right), i == j);
+                }
+            }
+            for category in labelled(m) {
+                let matches = classes
+                    .iter()
+                    .filter(|class| category.is_isomorphic(class))
+                    .count();
+                assert_eq!(matches, 1);
+            }
+        }
+    }
+
+    #[test]
+    fn distinguishes_tables_on_the_same_quiver() {
+        // the group of order 2 and the semilattice {1, e}
+        let group = Category::new(1, vec![0], vec![0], vec![0]);
+        let semilattice = Category::new(1, vec![0], vec![0], vec![1]);
+        assert!(group.validate().is_ok() && semilattice.validate().is_ok());
+        assert!(!group.is_isomorphic(&semilattice));
+        assert_eq!(group.isomorphism(&semilattice), None);
+    }
+
+    #[test]
+    fn counts_automorphisms() {
+        let orders = |category: Category| {
+            assert!(category.validate().is_ok());
+            category.automorphisms().order()
+        };
+        // the semilattice {1, e}
+        assert_eq!(orders(Category::new(1, vec![0], vec![0], vec![1])), 1);
+        // the monoid {1, a, b} with x∘y = x, which may swap a and b
+        assert_eq!(
+            orders(Category::new(1, vec![0, 0], vec![0, 0], vec![1, 1, 2, 2])),
+            2
+        );
+        // the discrete category on two objects
+        assert_eq!(orders(Category::new(2, vec![], vec![], vec![])), 2);
+        // two parallel arrows 0 → 1
+        assert_eq!(
+            orders(Category::new(
+                2,
+                vec![0, 0],
+                vec![1, 1],
+                vec![UNDEFINED; 4]
+            )),
+            2
+        );
+        // an isomorphism f: 0 → 1 with inverse g, which may swap 0 and 1
+        assert_eq!(
+            orders(Category::new(
+                2,
+                vec![0, 1],
+                vec![1, 0],
+                vec![UNDEFINED, 1, 0, UNDEFINED]
+            )),
+            2
+        );
+    }
+
+    // Orbit-stabilizer: the relabellings of the quiver act on the labelled
+    // categories, with the automorphisms as stabilizers.
+    #[test]
+    fn orbits_add_up_to_the_labelled_categories() {
+        for m in 1..=4 {
+            for n in 1..=m {
+                for (source, target) in quivers(n, m - n) {
+                    let relabellings =
+                        quiver_automorphisms(n, &source, &target).len();
+                    let classes = CategoryClassSet::new(
+                        n,
+                        source.clone(),
+                        target.clone(),
+                    );
+                    let mut orbits = 0;
+                    cursor!(category in &classes => {
+                        let automorphisms = category.automorphisms();
+                        orbits += relabellings / automorphisms.order();
+                    });
+                    let categories = CategorySet::new(n, source, target);
+                    assert_eq!(orbits, count(&categories));
+                }
+            }
+        }
+    }
+}
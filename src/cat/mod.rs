@@ -1,9 +1,29 @@
 use crate::cursor;
-use crate::set::{AtomSet, Set, Variable};
+use crate::set::{AtomSet, Backtrack, Set, Variable};
+
+mod builder;
+mod functor;
+mod iso;
+mod natural;
+mod quiver;
+
+pub(crate) use builder::is_valid_name;
+pub use builder::{CategoryBuilder, CategoryBuilderError, CategoryNames};
+pub use functor::{Functor, FunctorError, FunctorSet};
+pub use iso::{AutomorphismGroup, CategoryClassSet, Relabelling};
+pub use natural::{
+    NaturalTransformation, NaturalTransformationError, NaturalTransformationSet,
+};
+pub use quiver::{quivers, SizedCategoryClassSet};
+
+// The entry of a composition or action table at a pair that cannot be
+// composed. It is never a valid morphism or section.
+pub const UNDEFINED: usize = usize::MAX;
 
 #[derive(Debug, PartialEq)]
 pub enum CategoryError {
     IncompatibleComposition { g: usize, f: usize },
+    NotWellDefined { g: usize, f: usize },
     NonAssociative { morphisms: (usize, usize, usize) },
 }
 
@@ -17,6 +37,9 @@ impl std::fmt::Display for CategoryError {
                     g, f
                 )
             }
+            CategoryError::NotWellDefined { g, f } => {
+                write!(formatter, "Not well-defined: g={} and f={}", g, f)
+            }
             CategoryError::NonAssociative { morphisms } => {
                 write!(
                     formatter,
@@ -37,7 +60,8 @@ impl std::error::Error for CategoryError {}
 // This means for source and target that the first n values are omitted, where n is the number of objects.
 // For composition, the values for compositions involving identity morphisms are omitted.
 // These are the linearindexable tuples (i, j) where i < number_of_objects or j < number_of_objects.
-#[derive(Clone, Debug)]
+// Pairs that cannot be composed hold UNDEFINED in the composition table.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
 pub struct Category {
     number_of_objects: usize,
     number_of_morphisms: usize,
@@ -77,7 +101,7 @@ impl Category {
             source: vec![0; non_identity_morphisms],
             target: vec![0; non_identity_morphisms],
             composition: vec![
-                0;
+                UNDEFINED;
                 non_identity_morphisms * non_identity_morphisms
             ],
         };
@@ -122,35 +146,30 @@ impl Category {
         self.target[input - self.number_of_objects()]
     }
 
+    // The composite g∘f, or None if the target of f is not the source of g.
     #[inline(always)]
-    pub fn composition(&self, g: usize, f: usize) -> usize {
+    pub fn composition(&self, g: usize, f: usize) -> Option<usize> {
+        if self.target(f) != self.source(g) {
+            return None;
+        }
         if g < self.number_of_objects() {
-            if self.target(f) == self.source(g) {
-                return f;
-            } else {
-                return 0;
-            }
+            return Some(f);
         }
-
         if f < self.number_of_objects() {
-            if self.target(f) == self.source(g) {
-                return g;
-            } else {
-                return 0;
-            }
+            return Some(g);
         }
 
         let j = g - self.number_of_objects();
         let i = f - self.number_of_objects();
         let n = self.number_of_morphisms() - self.number_of_objects();
         let index = j * n + i;
-        self.composition[index]
+        Some(self.composition[index])
     }
 
     #[inline(always)]
     pub fn validate(&self) -> Result<(), CategoryError> {
-        self.validate_associativity()?;
         self.validate_well_definedness()?;
+        self.validate_associativity()?;
         Ok(())
     }
 
@@ -160,8 +179,12 @@ impl Category {
         cursor!(f in &morphisms => {
             cursor!(g in &morphisms => {
                 cursor!(h in &morphisms => {
-                    let left = self.composition(self.composition(*h, *g), *f);
-                    let right = self.composition(*h, self.composition(*g, *f));
+                    let left = self
+                        .composition(*h, *g)
+                        .and_then(|hg| self.composition(hg, *f));
+                    let right = self
+                        .composition(*g, *f)
+                        .and_then(|gf| self.composition(*h, gf));
 
                     if left != right {
                         return Err(CategoryError::NonAssociative {
@@ -177,17 +200,104 @@ impl Category {
     #[inline(always)]
     fn validate_well_definedness(&self) -> Result<(), CategoryError> {
         // we consider non-identity morphisms only
-        for f in self.number_of_objects()..self.number_of_morphisms() {
-            for g in self.number_of_objects()..self.number_of_morphisms() {
-                let target_f = self.target(f);
-                let source_g = self.source(g);
-                let composition = self.composition(g, f);
-
-                if target_f != source_g && composition != 0 {
-                    return Err(CategoryError::IncompatibleComposition {
-                        g,
-                        f,
-                    });
+        let n = self.number_of_morphisms() - self.number_of_objects();
+        for cell in 0..n * n {
+            self.validate_entry(cell)?;
+        }
+        Ok(())
+    }
+
+    // Checks that the table entry at `cell` is UNDEFINED exactly when the
+    // pair cannot be composed, and otherwise a morphism with the right source
+    // and target.
+    #[inline(always)]
+    fn validate_entry(&self, cell: usize) -> Result<(), CategoryError> {
+        let n = self.number_of_morphisms() - self.number_of_objects();
+        let g = cell / n + self.number_of_objects();
+        let f = cell % n + self.number_of_objects();
+        let entry = self.composition[cell];
+
+        if self.target(f) != self.source(g) {
+            if entry != UNDEFINED {
+                return Err(CategoryError::IncompatibleComposition { g, f });
+            }
+            return Ok(());
+        }
+        if entry >= self.number_of_morphisms()
+            || self.source(entry) != self.source(f)
+            || self.target(entry) != self.target(g)
+        {
+            return Err(CategoryError::NotWellDefined { g, f });
+        }
+        Ok(())
+    }
+
+    // Like `composition`, but treats the non-identity cells whose rank is
+    // `assigned` or higher as not yet known.
+    #[inline(always)]
+    fn partial_composition(
+        &self,
+        g: usize,
+        f: usize,
+        rank: &[usize],
+        assigned: usize,
+    ) -> Option<usize> {
+        if g < self.number_of_objects() || f < self.number_of_objects() {
+            return self.composition(g, f);
+        }
+        let j = g - self.number_of_objects();
+        let i = f - self.number_of_objects();
+        let n = self.number_of_morphisms() - self.number_of_objects();
+        let index = j * n + i;
+        if rank[index] < assigned && self.composition[index] != UNDEFINED {
+            Some(self.composition[index])
+        } else {
+            None
+        }
+    }
+
+    // Checks a partially filled composition table after `cell` has been
+    // assigned a well-defined entry, assuming all cells of lower rank were
+    // already checked.
+    // Only the associativity triples that involve `cell` and whose other
+    // compositions are all known are verified.
+    #[inline(always)]
+    fn validate_cell(
+        &self,
+        cell: usize,
+        rank: &[usize],
+    ) -> Result<(), CategoryError> {
+        let n = self.number_of_morphisms() - self.number_of_objects();
+        let g = cell / n + self.number_of_objects();
+        let f = cell % n + self.number_of_objects();
+        let assigned = rank[cell] + 1;
+
+        let compose = |g, f| self.partial_composition(g, f, rank, assigned);
+        let check = |h: usize, g: usize, f: usize| {
+            let left = compose(h, g).and_then(|hg| compose(hg, f));
+            let right = compose(g, f).and_then(|gf| compose(h, gf));
+            match (left, right) {
+                (Some(left), Some(right)) if left != right => {
+                    Err(CategoryError::NonAssociative {
+                        morphisms: (h, g, f),
+                    })
+                }
+                _ => Ok(()),
+            }
+        };
+
+        for x in 0..self.number_of_morphisms() {
+            // the cell as the inner composition on either side
+            check(g, f, x)?;
+            check(x, g, f)?;
+            for y in 0..self.number_of_morphisms() {
+                // the cell as the outer composition on either side
+                let xy = compose(x, y);
+                if xy == Some(g) {
+                    check(x, y, f)?;
+                }
+                if xy == Some(f) {
+                    check(g, x, y)?;
                 }
             }
         }
@@ -195,12 +305,23 @@ impl Category {
     }
 }
 
+// Enumerates the categories with the given objects and morphisms by a
+// backtracking search over the composition table: cells are filled in one at
+// a time and a partial table is abandoned as soon as it violates an
+// associativity triple that it fully determines. Only the cells of
+// composable pairs are searched, each over the morphisms in
+// Hom(source(f), target(g)); the others stay UNDEFINED. Cells are filled in
+// order of the larger of their two morphisms, so that the tables on the
+// first few morphisms are completed (and checked) early.
 #[derive(Clone)]
 pub struct CategorySet {
     number_of_objects: usize,
     number_of_morphisms: usize,
     source: Vec<usize>,
     target: Vec<usize>,
+    homs: Vec<Vec<usize>>,
+    order: Vec<usize>,
+    rank: Vec<usize>,
 }
 
 impl CategorySet {
@@ -211,15 +332,90 @@ impl CategorySet {
         target: Vec<usize>,
     ) -> Self {
         let number_of_morphisms = source.len() + number_of_objects;
+        let n = source.len();
+
+        // the candidates for g∘f, in increasing order
+        let source_of = |u: usize| {
+            if u < number_of_objects {
+                u
+            } else {
+                source[u - number_of_objects]
+            }
+        };
+        let target_of = |u: usize| {
+            if u < number_of_objects {
+                u
+            } else {
+                target[u - number_of_objects]
+            }
+        };
+        let homs: Vec<Vec<usize>> = (0..n * n)
+            .map(|cell| {
+                let (g, f) = (cell / n, cell % n);
+                if target[f] != source[g] {
+                    return Vec::new();
+                }
+                (0..number_of_morphisms)
+                    .filter(|&u| {
+                        source_of(u) == source[f] && target_of(u) == target[g]
+                    })
+                    .collect()
+            })
+            .collect();
+
+        let mut order: Vec<usize> = (0..n * n)
+            .filter(|&cell| target[cell % n] == source[cell / n])
+            .collect();
+        order.sort_by_key(|&cell| (usize::max(cell / n, cell % n), cell));
+        let mut rank = vec![usize::MAX; n * n];
+        for (position, &cell) in order.iter().enumerate() {
+            rank[cell] = position;
+        }
         Self {
             number_of_objects,
             number_of_morphisms,
             source,
             target,
+            homs,
+            order,
+            rank,
         }
     }
 }
 
+// Slots are the positions in the fill order, each running through the
+// hom-set of its cell.
+impl Backtrack<Category> for CategorySet {
+    #[inline(always)]
+    fn slots(&self, _: &Category) -> usize {
+        self.order.len()
+    }
+
+    #[inline(always)]
+    fn candidates(&self, _: &Category, position: usize) -> usize {
+        self.homs[self.order[position]].len()
+    }
+
+    #[inline(always)]
+    fn assign(
+        &self,
+        current: &mut Category,
+        position: usize,
+        candidate: usize,
+    ) -> bool {
+        let cell = self.order[position];
+        current.composition[cell] = self.homs[cell][candidate];
+        current.validate_cell(cell, &self.rank).is_ok()
+    }
+
+    #[inline(always)]
+    fn candidate(&self, current: &Category, position: usize) -> usize {
+        let cell = self.order[position];
+        let value = current.composition[cell];
+        self.homs[cell].partition_point(|&u| u < value)
+    }
+}
+
 impl Set<Category> for CategorySet {
     #[inline(always)]
     fn allocate(&self) -> Variable<Category> {
@@ -228,7 +424,7 @@ impl Set<Category> for CategorySet {
             self.source.clone(),
             self.target.clone(),
             vec![
-                0;
+                UNDEFINED;
                 (self.number_of_morphisms - self.number_of_objects)
                     * (self.number_of_morphisms - self.number_of_objects)
             ],
@@ -237,28 +433,13 @@ impl Set<Category> for CategorySet {
     }
 
     #[inline(always)]
-    fn next<'a>(&self, current: &'a mut Category) -> bool {
-        for i in 0..current.composition.len() {
-            current.composition[i] += 1;
-            if current.composition[i] < self.number_of_morphisms {
-                if current.validate().is_ok() {
-                    return true;
-                }
-                return self.next(current);
-            }
-            current.composition[i] = 0;
-        }
-        false
+    fn next(&self, current: &mut Category) -> bool {
+        self.search_next(current)
     }
 
     #[inline(always)]
-    fn reset<'a>(&self, current: &'a mut Category) -> bool {
-        for i in 0..current.composition.len() {
-            current.composition[i] = 0;
-        }
-        if current.validate().is_ok() {
-            return true;
-        }
-        self.next(current)
+    fn reset(&self, current: &mut Category) -> bool {
+        current.composition.fill(UNDEFINED);
+        self.search_first(current)
     }
 }
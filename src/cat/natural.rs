@@ -0,0 +1,381 @@
+use super::{Category, Functor, FunctorSet, UNDEFINED};
+use crate::cursor;
+use crate::set::{Backtrack, Set, Variable};
+use std::collections::HashMap;
+
+#[derive(Debug, PartialEq)]
+pub enum NaturalTransformationError {
+    NotWellDefined { object: usize },
+    NotNatural { f: usize },
+}
+
+impl std::fmt::Display for NaturalTransformationError {
+    fn fmt(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            NaturalTransformationError::NotWellDefined { object } => {
+                write!(formatter, "Not well-defined: object={}", object)
+            }
+            NaturalTransformationError::NotNatural { f } => {
+                write!(formatter, "Not natural: f={}", f)
+            }
+        }
+    }
+}
+
+impl std::error::Error for NaturalTransformationError {}
+
+// A natural transformation between two functors, stored as its component at
+// every object of the domain.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct NaturalTransformation {
+    components: Vec<usize>,
+}
+
+impl NaturalTransformation {
+    #[inline(always)]
+    pub fn new(components: Vec<usize>) -> Self {
+        NaturalTransformation { components }
+    }
+
+    #[inline(always)]
+    pub fn allocate(domain: &Category) -> Variable<Self> {
+        Variable::uninitialized(NaturalTransformation {
+            components: vec![0; domain.number_of_objects()],
+        })
+    }
+
+    #[inline(always)]
+    pub fn identity(domain: &Category, functor: &Functor) -> Self {
+        // the identity on F(x) is numbered like the object F(x)
+        NaturalTransformation {
+            components: (0..domain.number_of_objects())
+                .map(|x| functor.object(x))
+                .collect(),
+        }
+    }
+
+    #[inline(always)]
+    pub fn component(&self, object: usize) -> usize {
+        self.components[object]
+    }
+
+    // The vertical composite of `self` after `first`.
+    #[inline(always)]
+    pub fn compose(
+        &self,
+        first: &NaturalTransformation,
+        codomain: &Category,
+    ) -> Self {
+        NaturalTransformation {
+            components: self
+                .components
+                .iter()
+                .zip(&first.components)
+                .map(|(&beta, &alpha)| {
+                    codomain
+                        .composition(beta, alpha)
+                        .expect("components are composable")
+                })
+                .collect(),
+        }
+    }
+
+    #[inline(always)]
+    pub fn validate(
+        &self,
+        domain: &Category,
+        codomain: &Category,
+        source: &Functor,
+        target: &Functor,
+    ) -> Result<(), NaturalTransformationError> {
+        for object in 0..domain.number_of_objects() {
+            self.validate_component(codomain, source, target, object)?;
+        }
+        for f in 0..domain.number_of_morphisms() {
+            self.validate_naturality(domain, codomain, source, target, f)?;
+        }
+        Ok(())
+    }
+
+    #[inline(always)]
+    fn validate_component(
+        &self,
+        codomain: &Category,
+        source: &Functor,
+        target: &Functor,
+        object: usize,
+    ) -> Result<(), NaturalTransformationError> {
+        let component = self.component(object);
+        if codomain.source(component) != source.object(object)
+            || codomain.target(component) != target.object(object)
+        {
+            return Err(NaturalTransformationError::NotWellDefined { object });
+        }
+        Ok(())
+    }
+
+    // Checks G(f) ∘ α_x = α_y ∘ F(f) for f: x → y.
+    #[inline(always)]
+    fn validate_naturality(
+        &self,
+        domain: &Category,
+        codomain: &Category,
+        source: &Functor,
+        target: &Functor,
+        f: usize,
+    ) -> Result<(), NaturalTransformationError> {
+        let x = domain.source(f);
+        let y = domain.target(f);
+        let left = codomain.composition(target.morphism(f), self.component(x));
+        let right = codomain.composition(self.component(y), source.morphism(f));
+        if left != right {
+            return Err(NaturalTransformationError::NotNatural { f });
+        }
+        Ok(())
+    }
+}
+
+// Enumerates all natural transformations from `source` to `target`, two
+// functors from `domain` to `codomain`. Components are chosen one object at
+// a time; a partial choice is abandoned as soon as a morphism between
+// objects that already have a component violates naturality.
+#[derive(Clone, Debug)]
+pub struct NaturalTransformationSet<'a> {
+    domain: &'a Category,
+    codomain: &'a Category,
+    source: &'a Functor,
+    target: &'a Functor,
+}
+
+impl<'a> NaturalTransformationSet<'a> {
+    #[inline(always)]
+    pub fn new(
+        domain: &'a Category,
+        codomain: &'a Category,
+        source: &'a Functor,
+        target: &'a Functor,
+    ) -> Self {
+        NaturalTransformationSet {
+            domain,
+            codomain,
+            source,
+            target,
+        }
+    }
+
+    #[inline(always)]
+    fn is_consistent(
+        &self,
+        current: &NaturalTransformation,
+        last: usize,
+    ) -> bool {
+        if current
+            .validate_component(self.codomain, self.source, self.target, last)
+            .is_err()
+        {
+            return false;
+        }
+        for f in 0..self.domain.number_of_morphisms() {
+            let x = self.domain.source(f);
+            let y = self.domain.target(f);
+            if x > last || y > last || (x != last && y != last) {
+                continue;
+            }
+            if current
+                .validate_naturality(
+                    self.domain,
+                    self.codomain,
+                    self.source,
+                    self.target,
+                    f,
+                )
+                .is_err()
+            {
+                return false;
+            }
+        }
+        true
+    }
+}
+
+// Slots are the objects of the domain, each running through the morphisms
+// of the codomain as its component.
+impl Backtrack<NaturalTransformation> for NaturalTransformationSet<'_> {
+    #[inline(always)]
+    fn slots(&self, _: &NaturalTransformation) -> usize {
+        self.domain.number_of_objects()
+    }
+
+    #[inline(always)]
+    fn candidates(&self, _: &NaturalTransformation, _: usize) -> usize {
+        self.codomain.number_of_morphisms()
+    }
+
+    #[inline(always)]
+    fn assign(
+        &self,
+        current: &mut NaturalTransformation,
+        object: usize,
+        component: usize,
+    ) -> bool {
+        current.components[object] = component;
+        self.is_consistent(current, object)
+    }
+
+    #[inline(always)]
+    fn candidate(
+        &self,
+        current: &NaturalTransformation,
+        object: usize,
+    ) -> usize {
+        current.components[object]
+    }
+}
+
+impl Set<NaturalTransformation> for NaturalTransformationSet<'_> {
+    #[inline(always)]
+    fn allocate(&self) -> Variable<NaturalTransformation> {
+        NaturalTransformation::allocate(self.domain)
+    }
+
+    #[inline(always)]
+    fn next(&self, current: &mut NaturalTransformation) -> bool {
+        self.search_next(current)
+    }
+
+    #[inline(always)]
+    fn reset(&self, current: &mut NaturalTransformation) -> bool {
+        self.search_first(current)
+    }
+}
+
+impl Category {
+    // The functor category [domain, codomain]. Its objects are numbered in
+    // the order in which `FunctorSet` lists the functors; the non-identity
+    // morphisms follow in the order of their source, target and then of
+    // `NaturalTransformationSet`.
+    pub fn functor_category(
+        domain: &Category,
+        codomain: &Category,
+    ) -> Category {
+        let mut functors = Vec::new();
+        cursor!(functor in &FunctorSet::new(domain, codomain) => {
+            functors.push(functor.clone());
+        });
+
+        let mut transformations: Vec<(usize, usize, NaturalTransformation)> =
+            Vec::new();
+        for (index, functor) in functors.iter().enumerate() {
+            let identity = NaturalTransformation::identity(domain, functor);
+            transformations.push((index, index, identity));
+        }
+        for (i, source) in functors.iter().enumerate() {
+            for (j, target) in functors.iter().enumerate() {
+                let identity = NaturalTransformation::identity(domain, source);
+                let set = NaturalTransformationSet::new(
+                    domain, codomain, source, target,
+                );
+                cursor!(alpha in &set => {
+                    if i != j || *alpha != identity {
+                        transformations.push((i, j, alpha.clone()));
+                    }
+                });
+            }
+        }
+
+        let index: HashMap<_, _> = transformations
+            .iter()
+            .enumerate()
+            .map(|(position, transformation)| {
+                (transformation.clone(), position)
+            })
+            .collect();
+        let number_of_objects = functors.len();
+        let non_identity = &transformations[number_of_objects..];
+        let source = non_identity.iter().map(|(i, _, _)| *i).collect();
+        let target = non_identity.iter().map(|(_, j, _)| *j).collect();
+        let mut composition = Vec::with_capacity(non_identity.len().pow(2));
+        for (j, k, beta) in non_identity {
+            for (i, l, alpha) in non_identity {
+                if l != j {
+                    composition.push(UNDEFINED);
+                    continue;
+                }
+                let composite = (*i, *k, beta.compose(alpha, codomain));
+                composition.push(index[&composite]);
+            }
+        }
+        Category::new(number_of_objects, source, target, composition)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cat::SizedCategoryClassSet;
+
+    fn categories(largest: usize) -> Vec<Category> {
+        let mut categories = Vec::new();
+        for m in 1..=largest {
+            cursor!((_, category) in &SizedCategoryClassSet::new(m, None) => {
+                categories.push(category.clone());
+            });
+        }
+        categories
+    }
+
+    #[test]
+    fn functor_categories_validate() {
+        let categories = categories(3);
+        for domain in &categories {
+            for codomain in &categories {
+                let functors = Category::functor_category(domain, codomain);
+                assert!(functors.validate().is_ok());
+            }
+        }
+    }
+
+    #[test]
+    fn functors_from_the_terminal_category() {
+        let terminal = Category::new(1, vec![], vec![], vec![]);
+        for category in categories(4) {
+            let functors = Category::functor_category(&terminal, &category);
+            assert!(functors.is_isomorphic(&category));
+        }
+    }
+
+    #[test]
+    fn functors_from_an_arrow_are_morphisms() {
+        // 0 → 1
+        let arrow = Category::new(2, vec![0], vec![1], vec![UNDEFINED]);
+        for category in categories(4) {
+            let functors = Category::functor_category(&arrow, &category);
+            assert_eq!(
+                functors.number_of_objects(),
+                category.number_of_morphisms()
+            );
+        }
+    }
+
+    #[test]
+    fn counts_transformations_between_monoid_homomorphisms() {
+        // α: F ⇒ G is an element n with G(m)·n = n·F(m) for all m
+        let group = Category::new(1, vec![0], vec![0], vec![0]);
+        let trivial = Functor::new(vec![0], vec![0, 0]);
+        let identity = Functor::new(vec![0], vec![0, 1]);
+        let count = |source: &Functor, target: &Functor| {
+            let mut count = 0;
+            let set =
+                NaturalTransformationSet::new(&group, &group, source, target);
+            cursor!(alpha in &set => {
+                assert!(alpha.validate(&group, &group, source, target).is_ok());
+                count += 1;
+            });
+            count
+        };
+        assert_eq!(count(&identity, &identity), 2);
+        assert_eq!(count(&trivial, &trivial), 2);
+        assert_eq!(count(&trivial, &identity), 0);
+        assert_eq!(count(&identity, &trivial), 0);
+    }
+}
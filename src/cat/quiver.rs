@@ -0,0 +1,142 @@
+use super::iso::permutations;
+use super::{Category, CategoryClassSet};
+use crate::set::{Set, Variable};
+
+// The quivers with the given number of objects and arrows up to
+// relabelling. Each is returned as its `source` and `target` vectors, with
+// the arrows sorted by (source, target), and only the quiver whose sorted
+// arrow list is lexicographically smallest under permutation of the objects
+// is kept.
+pub fn quivers(
+    number_of_objects: usize,
+    number_of_arrows: usize,
+) -> Vec<(Vec<usize>, Vec<usize>)> {
+    let n = number_of_objects;
+    let symmetries = permutations(n);
+    let mut quivers = Vec::new();
+    let mut arrows = Vec::with_capacity(number_of_arrows);
+    extend_quivers(n, number_of_arrows, &symmetries, &mut arrows, &mut quivers);
+    quivers
+}
+
+// Arrows are coded as source·n + target and chosen in non-decreasing order.
+fn extend_quivers(
+    n: usize,
+    number_of_arrows: usize,
+    symmetries: &[Vec<usize>],
+    arrows: &mut Vec<usize>,
+    quivers: &mut Vec<(Vec<usize>, Vec<usize>)>,
+) {
+    if arrows.len() == number_of_arrows {
+        let canonical = symmetries.iter().all(|objects| {
+            let mut relabelled: Vec<usize> = arrows
+                .iter()
+                .map(|&arrow| objects[arrow / n] * n + objects[arrow % n])
+                .collect();
+            relabelled.sort_unstable();
+            relabelled >= *arrows
+        });
+        if canonical {
+            let source = arrows.iter().map(|&arrow| arrow / n).collect();
+            let target = arrows.iter().map(|&arrow| arrow % n).collect();
+            quivers.push((source, target));
+        }
+        return;
+    }
+    let first = arrows.last().copied().unwrap_or(0);
+    for arrow in first..n * n {
+        arrows.push(arrow);
+        extend_quivers(n, number_of_arrows, symmetries, arrows, quivers);
+        arrows.pop();
+    }
+}
+
+// Enumerates the categories with the given number of morphisms up to
+// isomorphism, optionally with a given number of objects as well. It runs
+// through the quivers up to relabelling, ordered by their number of objects,
+// and through the categories up to isomorphism on each as in
+// `CategoryClassSet`. Isomorphic categories have isomorphic quivers, so
+// every category is found exactly once. Each category comes with the index
+// of its quiver, which is where `next` carries on.
+#[derive(Clone)]
+pub struct SizedCategoryClassSet {
+    classes: Vec<CategoryClassSet>,
+}
+
+impl SizedCategoryClassSet {
+    #[inline(always)]
+    pub fn new(
+        number_of_morphisms: usize,
+        number_of_objects: Option<usize>,
+    ) -> Self {
+        let objects = match number_of_objects {
+            Some(n) => n..n + 1,
+            None => 0..number_of_morphisms + 1,
+        };
+        let mut classes = Vec::new();
+        for n in objects {
+            if n > number_of_morphisms || (n == 0 && number_of_morphisms > 0) {
+                continue;
+            }
+            for (source, target) in quivers(n, number_of_morphisms - n) {
+                classes.push(CategoryClassSet::new(n, source, target));
+            }
+        }
+        SizedCategoryClassSet { classes }
+    }
+
+    // Moves on to the first category on the quivers from `start` on.
+    #[inline(always)]
+    fn advance(&self, current: &mut (usize, Category), start: usize) -> bool {
+        for (index, class) in self.classes.iter().enumerate().skip(start) {
+            current.0 = index;
+            current.1 = class.allocate().get_uninitialized().clone();
+            if class.reset(&mut current.1) {
+                return true;
+            }
+        }
+        false
+    }
+}
+
+impl Set<(usize, Category)> for SizedCategoryClassSet {
+    #[inline(always)]
+    fn allocate(&self) -> Variable<(usize, Category)> {
+        Variable::uninitialized((0, Category::new(0, vec![], vec![], vec![])))
+    }
+
+    #[inline(always)]
+    fn next(&self, current: &mut (usize, Category)) -> bool {
+        let index = current.0;
+        if self.classes[index].next(&mut current.1) {
+            return true;
+        }
+        self.advance(current, index + 1)
+    }
+
+    #[inline(always)]
+    fn reset(&self, current: &mut (usize, Category)) -> bool {
+        self.advance(current, 0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cursor;
+
+    // OEIS A125696
+    #[test]
+    fn counts_categories_up_to_isomorphism() {
+        let counts: Vec<usize> = (1..=4)
+            .map(|m| {
+                let mut count = 0;
+                cursor!(_ in &SizedCategoryClassSet::new(m, None) => {
+                    count += 1;
+                });
+                count
+            })
+            .collect();
+        assert_eq!(counts, [1, 3, 11, 55]);
+    }
+}
@@ -3,3 +3,4 @@
 pub mod cat;
 pub mod psh;
 pub mod set;
+pub mod text;
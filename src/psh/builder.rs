@@ -0,0 +1,291 @@
+use super::{Presheaf, PresheafError};
+use crate::cat::{is_valid_name, Category, CategoryNames, UNDEFINED};
+use std::collections::HashMap;
+
+#[derive(Debug, PartialEq)]
+pub enum PresheafBuilderError {
+    InvalidName { name: String },
+    DuplicateName { name: String },
+    UnknownObject { name: String },
+    UnknownSection { name: String },
+    UnknownMorphism { name: String },
+    MalformedAction { action: String },
+    IncompatibleAction { s: String, f: String },
+    WrongAction { s: String, f: String, t: String },
+    ConflictingAction { s: String, f: String },
+    MissingAction { s: String, f: String },
+    NonAssociative { triple: (String, String, String) },
+}
+
+impl std::fmt::Display for PresheafBuilderError {
+    fn fmt(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PresheafBuilderError::InvalidName { name } => {
+                write!(formatter, "Invalid name: {:?}", name)
+            }
+            PresheafBuilderError::DuplicateName { name } => {
+                write!(formatter, "Duplicate name: {}", name)
+            }
+            PresheafBuilderError::UnknownObject { name } => {
+                write!(formatter, "Unknown object: {}", name)
+            }
+            PresheafBuilderError::UnknownSection { name } => {
+                write!(formatter, "Unknown section: {}", name)
+            }
+            PresheafBuilderError::UnknownMorphism { name } => {
+                write!(formatter, "Unknown morphism: {}", name)
+            }
+            PresheafBuilderError::MalformedAction { action } => {
+                write!(formatter, "Malformed action: {}", action)
+            }
+            PresheafBuilderError::IncompatibleAction { s, f } => {
+                write!(formatter, "Incompatible action: s={} and f={}", s, f)
+            }
+            PresheafBuilderError::WrongAction { s, f, t } => {
+                write!(
+                    formatter,
+                    "Wrong action: s={} and f={} cannot act to t={}",
+                    s, f, t
+                )
+            }
+            PresheafBuilderError::ConflictingAction { s, f } => {
+                write!(formatter, "Conflicting action: s={} and f={}", s, f)
+            }
+            PresheafBuilderError::MissingAction { s, f } => {
+                write!(formatter, "Missing action: s={} and f={}", s, f)
+            }
+            PresheafBuilderError::NonAssociative { triple } => {
+                write!(formatter, "Non-associative action: {:?}", triple)
+            }
+        }
+    }
+}
+
+impl std::error::Error for PresheafBuilderError {}
+
+#[derive(Clone, Debug)]
+enum Entry {
+    Section(String, String),
+    Action(String),
+}
+
+// Collects named sections over the named objects of a category and
+// equations "s·f = t", and turns them into a presheaf on the category.
+// Sections are declared before they are used, and their names have to be
+// valid as for `CategoryBuilder`. Identities act trivially, so equations
+// are only needed for the sections over the target of each non-identity
+// morphism, and every such pair needs exactly one value. Sections are
+// numbered in the order they are added.
+#[derive(Clone, Debug)]
+pub struct PresheafBuilder<'a> {
+    category: &'a Category,
+    names: &'a CategoryNames,
+    entries: Vec<Entry>,
+}
+
+impl<'a> PresheafBuilder<'a> {
+    #[inline(always)]
+    pub fn new(category: &'a Category, names: &'a CategoryNames) -> Self {
+        PresheafBuilder {
+            category,
+            names,
+            entries: Vec::new(),
+        }
+    }
+
+    // Declares a section `name` over `object`.
+    pub fn section(&mut self, name: &str, object: &str) -> &mut Self {
+        self.entries
+            .push(Entry::Section(name.to_string(), object.to_string()));
+        self
+    }
+
+    // Adds an equation "s·f = t".
+    pub fn action(&mut self, action: &str) -> &mut Self {
+        self.entries.push(Entry::Action(action.to_string()));
+        self
+    }
+
+    pub fn build(
+        &self,
+    ) -> Result<(Presheaf, Vec<String>), PresheafBuilderError> {
+        self.build_located().map_err(|(_, error)| error)
+    }
+
+    // Like `build`, but also tells which entry an error is about, counting
+    // the calls to `section` and `action` from 0; errors about the table as
+    // a whole come with None.
+    pub(crate) fn build_located(
+        &self,
+    ) -> Result<(Presheaf, Vec<String>), (Option<usize>, PresheafBuilderError)>
+    {
+        let mut resolution = Resolution::new(self, &self.entries);
+        for (index, entry) in self.entries.iter().enumerate() {
+            resolution
+                .add(entry)
+                .map_err(|error| (Some(index), error))?;
+        }
+        let Resolution {
+            presheaf, sections, ..
+        } = resolution;
+        let n = self.category.number_of_objects();
+
+        for f in n..self.category.number_of_morphisms() {
+            for (s, name) in sections.iter().enumerate() {
+                if presheaf.pi(s) == self.category.target(f)
+                    && presheaf.action(s, f).is_none()
+                {
+                    return Err((
+                        None,
+                        PresheafBuilderError::MissingAction {
+                            s: name.clone(),
+                            f: self.names.morphism_name(f).to_string(),
+                        },
+                    ));
+                }
+            }
+        }
+
+        // the table is well-defined by now, so only associativity can fail
+        if let Err(PresheafError::NonAssociative { triple: (s, f, g) }) =
+            presheaf.validate_associativity(self.category)
+        {
+            return Err((
+                None,
+                PresheafBuilderError::NonAssociative {
+                    triple: (
+                        sections[s].clone(),
+                        self.names.morphism_name(f).to_string(),
+                        self.names.morphism_name(g).to_string(),
+                    ),
+                },
+            ));
+        }
+        Ok((presheaf, sections))
+    }
+}
+
+// The presheaf as far as the entries read so far give it, with UNDEFINED
+// for the actions that are still missing.
+struct Resolution<'a> {
+    category: &'a Category,
+    names: &'a CategoryNames,
+    presheaf: Presheaf,
+    sections: Vec<String>,
+    section_index: HashMap<String, usize>,
+}
+
+impl<'a> Resolution<'a> {
+    fn new(builder: &PresheafBuilder<'a>, entries: &[Entry]) -> Self {
+        let m = entries
+            .iter()
+            .filter(|entry| matches!(entry, Entry::Section(..)))
+            .count();
+        let k = builder.category.number_of_morphisms()
+            - builder.category.number_of_objects();
+        Resolution {
+            category: builder.category,
+            names: builder.names,
+            presheaf: Presheaf::new(
+                builder.category,
+                vec![0; m],
+                vec![UNDEFINED; m * k],
+            ),
+            sections: Vec::with_capacity(m),
+            section_index: HashMap::new(),
+        }
+    }
+
+    fn add(&mut self, entry: &Entry) -> Result<(), PresheafBuilderError> {
+        match entry {
+            Entry::Section(name, object) => {
+                if !is_valid_name(name) {
+                    return Err(PresheafBuilderError::InvalidName {
+                        name: name.clone(),
+                    });
+                }
+                if self.section_index.contains_key(name) {
+                    return Err(PresheafBuilderError::DuplicateName {
+                        name: name.clone(),
+                    });
+                }
+                let s = self.sections.len();
+                self.presheaf.pi[s] =
+                    self.names.object(object).ok_or_else(|| {
+                        PresheafBuilderError::UnknownObject {
+                            name: object.clone(),
+                        }
+                    })?;
+                self.section_index.insert(name.clone(), s);
+                self.sections.push(name.clone());
+            }
+            Entry::Action(action) => self.action(action)?,
+        }
+        Ok(())
+    }
+
+    #[inline(always)]
+    fn section(&self, name: &str) -> Result<usize, PresheafBuilderError> {
+        self.section_index.get(name).copied().ok_or_else(|| {
+            PresheafBuilderError::UnknownSection {
+                name: name.to_string(),
+            }
+        })
+    }
+
+    // Reads "s·f = t" and sets s·f to t.
+    fn action(&mut self, action: &str) -> Result<(), PresheafBuilderError> {
+        let malformed = || PresheafBuilderError::MalformedAction {
+            action: action.to_string(),
+        };
+        let (left, t) = action.split_once('=').ok_or_else(malformed)?;
+        let (s, f) = left.split_once('·').ok_or_else(malformed)?;
+        let parts = [s.trim(), f.trim(), t.trim()];
+        if parts
+            .iter()
+            .any(|part| part.is_empty() || part.contains(['=', '·']))
+        {
+            return Err(malformed());
+        }
+        let s = self.section(parts[0])?;
+        let f = self.names.morphism(parts[1]).ok_or_else(|| {
+            PresheafBuilderError::UnknownMorphism {
+                name: parts[1].to_string(),
+            }
+        })?;
+        let t = self.section(parts[2])?;
+
+        let named = |s: usize, f: usize| {
+            (
+                self.sections[s].clone(),
+                self.names.morphism_name(f).to_string(),
+            )
+        };
+        if self.presheaf.pi(s) != self.category.target(f) {
+            let (s, f) = named(s, f);
+            return Err(PresheafBuilderError::IncompatibleAction { s, f });
+        }
+        if self.presheaf.pi(t) != self.category.source(f) {
+            let (s, f) = named(s, f);
+            let t = self.sections[t].clone();
+            return Err(PresheafBuilderError::WrongAction { s, f, t });
+        }
+        let n = self.category.number_of_objects();
+        let conflicting = if f < n {
+            // identities act as they must
+            s != t
+        } else {
+            let index = s + (f - n) * self.presheaf.number_of_sections();
+            let entry = &mut self.presheaf.action[index];
+            if *entry == UNDEFINED {
+                *entry = t;
+            }
+            *entry != t
+        };
+        if conflicting {
+            let (s, f) = named(s, f);
+            return Err(PresheafBuilderError::ConflictingAction { s, f });
+        }
+        Ok(())
+    }
+}
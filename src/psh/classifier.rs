@@ -0,0 +1,218 @@
+use super::yoneda::morphisms_into;
+use super::{Presheaf, PresheafMorphism, Subpresheaf};
+use crate::cat::Category;
+use std::collections::HashMap;
+
+// The subobject classifier Ω. Its sections over an object c are the sieves
+// on c, listed object by object, and the action is pullback of sieves. A
+// sieve is stored as the membership of every morphism of the category.
+#[derive(Clone, Debug)]
+pub struct SubobjectClassifier {
+    pub presheaf: Presheaf,
+    // 1 → Ω, picking the maximal sieves; 1 is `Presheaf::terminal`
+    pub truth: PresheafMorphism,
+    sieves: Vec<Vec<bool>>,
+    index: HashMap<(usize, Vec<bool>), usize>,
+}
+
+impl Presheaf {
+    pub fn subobject_classifier(category: &Category) -> SubobjectClassifier {
+        let mut sieves = Vec::new();
+        let mut pi = Vec::new();
+        for object in 0..category.number_of_objects() {
+            for sieve in sieves_on(category, object) {
+                sieves.push(sieve);
+                pi.push(object);
+            }
+        }
+        let index: HashMap<(usize, Vec<bool>), usize> = sieves
+            .iter()
+            .zip(&pi)
+            .enumerate()
+            .map(|(section, (sieve, &object))| {
+                ((object, sieve.clone()), section)
+            })
+            .collect();
+
+        // f*S = {g : f∘g ∈ S}
+        let presheaf = Presheaf::from_fn(category, pi, |s, f| {
+            let pulled_back = (0..category.number_of_morphisms())
+                .map(|g| {
+                    category.composition(f, g).is_some_and(|fg| sieves[s][fg])
+                })
+                .collect();
+            Some(index[&(category.source(f), pulled_back)])
+        });
+
+        let truth = PresheafMorphism::new(
+            (0..category.number_of_objects())
+                .map(|object| {
+                    let maximal = (0..category.number_of_morphisms())
+                        .map(|g| category.target(g) == object)
+                        .collect();
+                    index[&(object, maximal)]
+                })
+                .collect(),
+        );
+
+        SubobjectClassifier {
+            presheaf,
+            truth,
+            sieves,
+            index,
+        }
+    }
+}
+
+impl SubobjectClassifier {
+    #[inline(always)]
+    pub fn sieve(&self, section: usize) -> &[bool] {
+        &self.sieves[section]
+    }
+
+    // The section standing for `sieve` on `object`.
+    #[inline(always)]
+    pub fn section(&self, object: usize, sieve: &[bool]) -> usize {
+        self.index[&(object, sieve.to_vec())]
+    }
+
+    // The classifying map χ: P → Ω of a subpresheaf A of P, sending a
+    // section s over c to the sieve {f : s·f ∈ A} on c.
+    pub fn classify(
+        &self,
+        category: &Category,
+        presheaf: &Presheaf,
+        subpresheaf: &Subpresheaf,
+    ) -> PresheafMorphism {
+        PresheafMorphism::new(
+            (0..presheaf.number_of_sections())
+                .map(|s| {
+                    let object = presheaf.pi(s);
+                    let sieve: Vec<bool> = (0..category.number_of_morphisms())
+                        .map(|f| {
+                            presheaf
+                                .action(s, f)
+                                .is_some_and(|t| subpresheaf.contains(t))
+                        })
+                        .collect();
+                    self.section(object, &sieve)
+                })
+                .collect(),
+        )
+    }
+}
+
+// All sieves on `object`: sets of morphisms into `object` closed under
+// precomposition.
+fn sieves_on(category: &Category, object: usize) -> Vec<Vec<bool>> {
+    let morphisms = morphisms_into(category, object);
+    let mut sieves = Vec::new();
+    let mut sieve = vec![false; category.number_of_morphisms()];
+    extend_sieves(category, &morphisms, 0, &mut sieve, &mut sieves);
+    sieves
+}
+
+fn extend_sieves(
+    category: &Category,
+    morphisms: &[usize],
+    position: usize,
+    sieve: &mut Vec<bool>,
+    sieves: &mut Vec<Vec<bool>>,
+) {
+    if position == morphisms.len() {
+        let closed = morphisms.iter().all(|&h| {
+            !sieve[h]
+                || (0..category.number_of_morphisms()).all(|g| {
+                    category.composition(h, g).is_none_or(|hg| sieve[hg])
+                })
+        });
+        if closed {
+            sieves.push(sieve.clone());
+        }
+        return;
+    }
+    let h = morphisms[position];
+    for member in [false, true] {
+        sieve[h] = member;
+        extend_sieves(category, morphisms, position + 1, sieve, sieves);
+    }
+    sieve[h] = false;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cat::{CategoryClassSet, UNDEFINED};
+    use crate::cursor;
+    use crate::psh::{PresheafMorphismSet, PresheafSet};
+    use crate::set::Set;
+    use std::collections::HashSet;
+
+    fn monoids(n: usize) -> Vec<Category> {
+        let mut monoids = Vec::new();
+        let set = CategoryClassSet::new(1, vec![0; n - 1], vec![0; n - 1]);
+        cursor!(monoid in &set => {
+            monoids.push(monoid.clone());
+        });
+        monoids
+    }
+
+    #[test]
+    fn counts_sieves() {
+        // a group has only the empty and the maximal sieve, the idempotent
+        // e also has the right ideal {e}
+        let mut counts: Vec<usize> = monoids(2)
+            .iter()
+            .map(|monoid| {
+                Presheaf::subobject_classifier(monoid)
+                    .presheaf
+                    .number_of_sections()
+            })
+            .collect();
+        counts.sort_unstable();
+        assert_eq!(counts, [2, 3]);
+
+        // 0 → 1, with sieves ∅, {id} on 0 and ∅, {f}, {f, id} on 1
+        let arrow = Category::new(2, vec![0], vec![1], vec![UNDEFINED]);
+        let omega = Presheaf::subobject_classifier(&arrow).presheaf;
+        assert_eq!(omega.fibre(0).len(), 2);
+        assert_eq!(omega.fibre(1).len(), 3);
+    }
+
+    #[test]
+    fn classifying_maps_are_the_maps_into_omega() {
+        for n in 1..=3 {
+            for monoid in monoids(n) {
+                let classifier = Presheaf::subobject_classifier(&monoid);
+                let omega = &classifier.presheaf;
+                assert!(omega.validate(&monoid).is_ok());
+                for m in 1..=3 {
+                    let pi = vec![0; m];
+                    let acts = PresheafSet::new(&monoid, &pi);
+                    cursor!(act in &acts => {
+                        let mut maps = HashSet::new();
+                        for bits in 0..1 << m {
+                            let members =
+                                (0..m).map(|s| bits >> s & 1 == 1).collect();
+                            let subpresheaf = Subpresheaf::new(members);
+                            if subpresheaf.validate(&monoid, act).is_err() {
+                                continue;
+                            }
+                            let chi =
+                                classifier.classify(&monoid, act, &subpresheaf);
+                            assert!(chi.validate(&monoid, act, omega).is_ok());
+                            maps.insert(chi);
+                        }
+                        let mut count = 0;
+                        let morphisms =
+                            PresheafMorphismSet::new(&monoid, act, omega);
+                        cursor!(_ in &morphisms => {
+                            count += 1;
+                        });
+                        assert_eq!(maps.len(), count);
+                    });
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,315 @@
+use super::{Presheaf, PresheafMorphism};
+use crate::cat::Category;
+
+// A colimit of a diagram of presheaves: the colimit presheaf together with
+// its coprojections from the objects of the diagram.
+#[derive(Clone, Debug)]
+pub struct Colimit {
+    pub presheaf: Presheaf,
+    pub coprojections: Vec<PresheafMorphism>,
+}
+
+impl Presheaf {
+    // The initial presheaf, which has no sections.
+    pub fn initial(category: &Category) -> Colimit {
+        Presheaf::coproduct(category, &[])
+    }
+
+    pub fn binary_coproduct(
+        category: &Category,
+        left: &Presheaf,
+        right: &Presheaf,
+    ) -> Colimit {
+        Presheaf::coproduct(category, &[left, right])
+    }
+
+    // The coproduct of `summands`: the sections of the summands one after
+    // the other, each keeping its own action.
+    pub fn coproduct(category: &Category, summands: &[&Presheaf]) -> Colimit {
+        let mut offsets = Vec::with_capacity(summands.len());
+        let mut owners = Vec::new();
+        let mut pi = Vec::new();
+        for (i, summand) in summands.iter().enumerate() {
+            offsets.push(pi.len());
+            for s in 0..summand.number_of_sections() {
+                owners.push((i, s));
+                pi.push(summand.pi(s));
+            }
+        }
+        let presheaf = Presheaf::from_fn(category, pi, |s, f| {
+            let (i, t) = owners[s];
+            Some(offsets[i] + summands[i].action(t, f)?)
+        });
+        let coprojections = summands
+            .iter()
+            .zip(&offsets)
+            .map(|(summand, &offset)| {
+                PresheafMorphism::new(
+                    (0..summand.number_of_sections())
+                        .map(|s| offset + s)
+                        .collect(),
+                )
+            })
+            .collect();
+        Colimit {
+            presheaf,
+            coprojections,
+        }
+    }
+
+    // The coequalizer of two morphisms `first`, `second` from `source` to
+    // `target`: the quotient of `target` by the smallest congruence, that
+    // is equivalence relation compatible with the action, identifying
+    // first(s) with second(s) for every section s of `source`. Classes are
+    // numbered in the order of their smallest section.
+    pub fn coequalizer(
+        category: &Category,
+        source: &Presheaf,
+        target: &Presheaf,
+        first: &PresheafMorphism,
+        second: &PresheafMorphism,
+    ) -> Colimit {
+        let mut parent: Vec<usize> = (0..target.number_of_sections()).collect();
+        let mut pending: Vec<(usize, usize)> = (0..source.number_of_sections())
+            .map(|s| (first.image(s), second.image(s)))
+            .collect();
+        while let Some((a, b)) = pending.pop() {
+            let root_a = find(&mut parent, a);
+            let root_b = find(&mut parent, b);
+            if root_a == root_b {
+                continue;
+            }
+            parent[usize::max(root_a, root_b)] = usize::min(root_a, root_b);
+            // a and b lie in the same fibre, so they can be acted on alike
+            for f in 0..category.number_of_morphisms() {
+                if let (Some(a_f), Some(b_f)) =
+                    (target.action(a, f), target.action(b, f))
+                {
+                    pending.push((a_f, b_f));
+                }
+            }
+        }
+
+        let (classes, representatives) = number_classes(&mut parent);
+        let pi = representatives.iter().map(|&s| target.pi(s)).collect();
+        let presheaf = Presheaf::from_fn(category, pi, |class, f| {
+            Some(classes[target.action(representatives[class], f)?])
+        });
+        Colimit {
+            presheaf,
+            coprojections: vec![PresheafMorphism::new(classes)],
+        }
+    }
+
+    // The pushout of `first`: R → P and `second`: R → Q, with its
+    // coprojections from P and Q.
+    pub fn pushout(
+        category: &Category,
+        source: &Presheaf,
+        left: &Presheaf,
+        right: &Presheaf,
+        first: &PresheafMorphism,
+        second: &PresheafMorphism,
+    ) -> Colimit {
+        let coproduct = Presheaf::binary_coproduct(category, left, right);
+        let coequalizer = Presheaf::coequalizer(
+            category,
+            source,
+            &coproduct.presheaf,
+            &coproduct.coprojections[0].compose(first),
+            &coproduct.coprojections[1].compose(second),
+        );
+        let quotient = &coequalizer.coprojections[0];
+        let coprojections = coproduct
+            .coprojections
+            .iter()
+            .map(|coprojection| quotient.compose(coprojection))
+            .collect();
+        Colimit {
+            presheaf: coequalizer.presheaf,
+            coprojections,
+        }
+    }
+}
+
+// The representative of the class of `s`, which is its smallest section.
+pub(crate) fn find(parent: &mut [usize], s: usize) -> usize {
+    let mut root = s;
+    while parent[root] != root {
+        root = parent[root];
+    }
+    let mut s = s;
+    while parent[s] != root {
+        let next = parent[s];
+        parent[s] = root;
+        s = next;
+    }
+    root
+}
+
+// Numbers the classes in the order of their smallest section, giving the
+// class of every section and the smallest section of every class.
+pub(crate) fn number_classes(parent: &mut [usize]) -> (Vec<usize>, Vec<usize>) {
+    let mut classes = vec![0; parent.len()];
+    let mut representatives = Vec::new();
+    for s in 0..parent.len() {
+        let root = find(parent, s);
+        if root == s {
+            classes[s] = representatives.len();
+            representatives.push(s);
+        } else {
+            classes[s] = classes[root];
+        }
+    }
+    (classes, representatives)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cat::SizedCategoryClassSet;
+    use crate::cursor;
+    use crate::psh::{PresheafMorphismSet, SizedPresheafSet};
+    use crate::set::Set;
+
+    fn categories() -> Vec<Category> {
+        let mut categories = Vec::new();
+        for m in 1..=3 {
+            cursor!((_, category) in &SizedCategoryClassSet::new(m, None) => {
+                categories.push(category.clone());
+            });
+        }
+        categories
+    }
+
+    fn presheaves(category: &Category, largest: usize) -> Vec<Presheaf> {
+        let mut presheaves = Vec::new();
+        for sections in 0..=largest {
+            let set = SizedPresheafSet::new(category, sections);
+            cursor!(presheaf in &set => {
+                presheaves.push(presheaf.clone());
+            });
+        }
+        presheaves
+    }
+
+    fn morphisms(
+        category: &Category,
+        source: &Presheaf,
+        target: &Presheaf,
+    ) -> Vec<PresheafMorphism> {
+        let mut morphisms = Vec::new();
+        let set = PresheafMorphismSet::new(category, source, target);
+        cursor!(phi in &set => {
+            morphisms.push(phi.clone());
+        });
+        morphisms
+    }
+
+    fn pairs<'a, T, U>(
+        left: &'a [T],
+        right: &'a [U],
+    ) -> impl Iterator<Item = (&'a T, &'a U)> {
+        left.iter()
+            .flat_map(move |a| right.iter().map(move |b| (a, b)))
+    }
+
+    fn assert_colimit(
+        category: &Category,
+        colimit: &Colimit,
+        summands: &[&Presheaf],
+    ) {
+        assert!(colimit.presheaf.validate(category).is_ok());
+        for (coprojection, summand) in
+            colimit.coprojections.iter().zip(summands)
+        {
+            let valid =
+                coprojection.validate(category, summand, &colimit.presheaf);
+            assert!(valid.is_ok());
+        }
+    }
+
+    #[test]
+    fn initial_and_coproducts_are_universal() {
+        for category in categories() {
+            let presheaves = presheaves(&category, 2);
+            let initial = Presheaf::initial(&category);
+            assert_colimit(&category, &initial, &[]);
+            for r in &presheaves {
+                assert_eq!(morphisms(&category, &initial.presheaf, r).len(), 1);
+            }
+            for (p, q) in pairs(&presheaves, &presheaves) {
+                let coproduct = Presheaf::binary_coproduct(&category, p, q);
+                assert_colimit(&category, &coproduct, &[p, q]);
+                for r in &presheaves {
+                    assert_eq!(
+                        morphisms(&category, &coproduct.presheaf, r).len(),
+                        morphisms(&category, p, r).len()
+                            * morphisms(&category, q, r).len()
+                    );
+                }
+            }
+        }
+    }
+
+    // Hom(C, R) is the set of maps Q → R that agree after both morphisms.
+    #[test]
+    fn coequalizers_are_universal() {
+        for category in categories() {
+            // larger targets, so that the congruence has room to spread
+            let targets = presheaves(&category, 3);
+            let presheaves = presheaves(&category, 2);
+            for (p, q) in pairs(&presheaves, &targets) {
+                let parallel = morphisms(&category, p, q);
+                for (first, second) in pairs(&parallel, &parallel) {
+                    let coequalizer =
+                        Presheaf::coequalizer(&category, p, q, first, second);
+                    assert_colimit(&category, &coequalizer, &[q]);
+                    for r in &presheaves {
+                        let agreeing = morphisms(&category, q, r)
+                            .iter()
+                            .filter(|chi| {
+                                chi.compose(first) == chi.compose(second)
+                            })
+                            .count();
+                        let maps =
+                            morphisms(&category, &coequalizer.presheaf, r);
+                        assert_eq!(maps.len(), agreeing);
+                    }
+                }
+            }
+        }
+    }
+
+    // Hom(P +_T Q, R) is the set of pairs P → R, Q → R that agree on T.
+    #[test]
+    fn pushouts_are_universal() {
+        for category in categories() {
+            let presheaves = presheaves(&category, 2);
+            for (p, q) in pairs(&presheaves, &presheaves) {
+                for t in &presheaves {
+                    let firsts = morphisms(&category, t, p);
+                    let seconds = morphisms(&category, t, q);
+                    for (first, second) in pairs(&firsts, &seconds) {
+                        let pushout = Presheaf::pushout(
+                            &category, t, p, q, first, second,
+                        );
+                        assert_colimit(&category, &pushout, &[p, q]);
+                        for r in &presheaves {
+                            let lefts = morphisms(&category, p, r);
+                            let rights = morphisms(&category, q, r);
+                            let agreeing = pairs(&lefts, &rights)
+                                .filter(|(a, b)| {
+                                    a.compose(first) == b.compose(second)
+                                })
+                                .count();
+                            let maps =
+                                morphisms(&category, &pushout.presheaf, r);
+                            assert_eq!(maps.len(), agreeing);
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,108 @@
+use super::Presheaf;
+use crate::cat::{Category, Functor, UNDEFINED};
+use std::collections::HashMap;
+
+impl Presheaf {
+    // The category of elements. Its objects are the sections, and besides
+    // the identities it has a morphism (s, f): s·f → s for every non-identity
+    // f with pi(s) = target(f), numbered by f and then by s. Composition is
+    // (s, f)∘(s·f, g) = (s, f∘g).
+    pub fn category_of_elements(&self, category: &Category) -> Category {
+        let elements = self.elements(category);
+        let number_of_objects = self.number_of_sections();
+        let index: HashMap<_, _> = elements
+            .iter()
+            .enumerate()
+            .map(|(position, &(s, f, _))| {
+                ((s, f), position + number_of_objects)
+            })
+            .collect();
+        let source = elements.iter().map(|&(_, _, s_f)| s_f).collect();
+        let target = elements.iter().map(|&(s, _, _)| s).collect();
+        let mut composition = Vec::with_capacity(elements.len().pow(2));
+        for &(s, f, s_f) in &elements {
+            for &(t, g, _) in &elements {
+                if s_f != t {
+                    composition.push(UNDEFINED);
+                    continue;
+                }
+                let entry = match category.composition(f, g) {
+                    Some(h) if h >= category.number_of_objects() => {
+                        index[&(s, h)]
+                    }
+                    // f∘g is an identity, so the composite is the one on s
+                    Some(_) => s,
+                    None => UNDEFINED,
+                };
+                composition.push(entry);
+            }
+        }
+        Category::new(number_of_objects, source, target, composition)
+    }
+
+    // The projection from the category of elements onto the base category,
+    // sending s to pi(s) and (s, f) to f.
+    pub fn projection(&self, category: &Category) -> Functor {
+        let objects: Vec<usize> = self.pi.clone();
+        let morphisms = objects
+            .iter()
+            .copied()
+            .chain(self.elements(category).into_iter().map(|(_, f, _)| f))
+            .collect();
+        Functor::new(objects, morphisms)
+    }
+
+    // The non-identity morphisms (s, f) of the category of elements in
+    // order, each together with its source s·f.
+    fn elements(&self, category: &Category) -> Vec<(usize, usize, usize)> {
+        let mut elements = vec![];
+        for f in category.number_of_objects()..category.number_of_morphisms() {
+            for s in 0..self.number_of_sections() {
+                if let Some(s_f) = self.action(s, f) {
+                    elements.push((s, f, s_f));
+                }
+            }
+        }
+        elements
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cat::SizedCategoryClassSet;
+    use crate::cursor;
+    use crate::psh::SizedPresheafSet;
+    use crate::set::Set;
+
+    #[test]
+    fn category_of_elements_projects_onto_the_base() {
+        for m in 1..=3 {
+            cursor!((_, category) in &SizedCategoryClassSet::new(m, None) => {
+                for sections in 0..=3 {
+                    let presheaves = SizedPresheafSet::new(category, sections);
+                    cursor!(presheaf in &presheaves => {
+                        let elements = presheaf.category_of_elements(category);
+                        assert!(elements.validate().is_ok());
+                        assert_eq!(elements.number_of_objects(), sections);
+                        let projection = presheaf.projection(category);
+                        let valid = projection.validate(&elements, category);
+                        assert!(valid.is_ok());
+                    });
+                }
+            });
+        }
+    }
+
+    // The elements of y(c) form the slice over c, which for the target c of
+    // an arrow is the arrow again.
+    #[test]
+    fn elements_of_a_representable_form_a_slice() {
+        // 0 → 1
+        let arrow = Category::new(2, vec![0], vec![1], vec![UNDEFINED]);
+        let elements =
+            Presheaf::representable(&arrow, 1).category_of_elements(&arrow);
+        assert!(elements.validate().is_ok());
+        assert!(elements.is_isomorphic(&arrow));
+    }
+}
@@ -0,0 +1,254 @@
+use super::yoneda::morphisms_into;
+use super::{Limit, Presheaf, PresheafMorphism, PresheafMorphismSet};
+use crate::cat::Category;
+use crate::cursor;
+use crate::set::Set;
+use std::collections::HashMap;
+
+// The exponential Q^P of two presheaves. Its sections over an object c are
+// the morphisms y(c) × P → Q, listed object by object in the order of
+// `PresheafMorphismSet`, where y(c) × P is `Presheaf::binary_product`.
+#[derive(Clone, Debug)]
+pub struct Exponential {
+    pub presheaf: Presheaf,
+    // Q^P × P → Q, with Q^P × P given by `Presheaf::binary_product`
+    pub evaluation: PresheafMorphism,
+    base: Presheaf,
+    transformations: Vec<PresheafMorphism>,
+    products: Vec<Limit>,
+    index: HashMap<(usize, PresheafMorphism), usize>,
+}
+
+impl Presheaf {
+    // The exponential `target`^`base`.
+    pub fn exponential(
+        category: &Category,
+        base: &Presheaf,
+        target: &Presheaf,
+    ) -> Exponential {
+        let products: Vec<Limit> = (0..category.number_of_objects())
+            .map(|object| {
+                let representable = Presheaf::representable(category, object);
+                Presheaf::binary_product(category, &representable, base)
+            })
+            .collect();
+        let pairs: Vec<HashMap<(usize, usize), usize>> =
+            products.iter().map(pair_index).collect();
+
+        let mut transformations = Vec::new();
+        let mut pi = Vec::new();
+        for (object, product) in products.iter().enumerate() {
+            let set =
+                PresheafMorphismSet::new(category, &product.presheaf, target);
+            cursor!(theta in &set => {
+                transformations.push(theta.clone());
+                pi.push(object);
+            });
+        }
+        let index: HashMap<(usize, PresheafMorphism), usize> = transformations
+            .iter()
+            .zip(&pi)
+            .enumerate()
+            .map(|(section, (theta, &object))| {
+                ((object, theta.clone()), section)
+            })
+            .collect();
+
+        // (θ·f)(u, p) = θ(f∘u, p) for f: d → c and (u, p) over y(d) × P
+        let sections_into: Vec<Vec<usize>> = (0..category.number_of_objects())
+            .map(|object| morphisms_into(category, object))
+            .collect();
+        let presheaf = Presheaf::from_fn(category, pi, |s, f| {
+            let c = category.target(f);
+            let d = category.source(f);
+            let theta = &transformations[s];
+            let restricted = PresheafMorphism::new(
+                (0..products[d].presheaf.number_of_sections())
+                    .map(|section| {
+                        let u = products[d].projections[0].image(section);
+                        let p = products[d].projections[1].image(section);
+                        let fu = category
+                            .composition(f, sections_into[d][u])
+                            .expect("u lies over the source of f");
+                        let v = sections_into[c].binary_search(&fu).unwrap();
+                        theta.image(pairs[c][&(v, p)])
+                    })
+                    .collect(),
+            );
+            Some(index[&(d, restricted)])
+        });
+
+        // ev(θ, p) = θ(id_c, p) for θ and p over c
+        let product = Presheaf::binary_product(category, &presheaf, base);
+        let evaluation = PresheafMorphism::new(
+            (0..product.presheaf.number_of_sections())
+                .map(|section| {
+                    let s = product.projections[0].image(section);
+                    let p = product.projections[1].image(section);
+                    let c = presheaf.pi(s);
+                    transformations[s].image(pairs[c][&(0, p)])
+                })
+                .collect(),
+        );
+
+        Exponential {
+            presheaf,
+            evaluation,
+            base: base.clone(),
+            transformations,
+            products,
+            index,
+        }
+    }
+}
+
+impl Exponential {
+    // The morphism y(c) × P → Q that the section `section` over c stands
+    // for.
+    #[inline(always)]
+    pub fn transformation(&self, section: usize) -> &PresheafMorphism {
+        &self.transformations[section]
+    }
+
+    // The transpose R → Q^P of a morphism R × P → Q, where R × P is
+    // `Presheaf::binary_product(category, source, base)`: a section r over c
+    // goes to (u, p) ↦ morphism(r·u, p).
+    pub fn curry(
+        &self,
+        category: &Category,
+        source: &Presheaf,
+        morphism: &PresheafMorphism,
+    ) -> PresheafMorphism {
+        let product = Presheaf::binary_product(category, source, &self.base);
+        let pairs = pair_index(&product);
+        PresheafMorphism::new(
+            (0..source.number_of_sections())
+                .map(|r| {
+                    let c = source.pi(r);
+                    let sections_into = morphisms_into(category, c);
+                    let local = &self.products[c];
+                    let theta = PresheafMorphism::new(
+                        (0..local.presheaf.number_of_sections())
+                            .map(|section| {
+                                let u = local.projections[0].image(section);
+                                let p = local.projections[1].image(section);
+                                let ru = source
+                                    .action(r, sections_into[u])
+                                    .expect("u lies over the object of r");
+                                morphism.image(pairs[&(ru, p)])
+                            })
+                            .collect(),
+                    );
+                    self.index[&(c, theta)]
+                })
+                .collect(),
+        )
+    }
+}
+
+// The section of a binary product with given components.
+fn pair_index(product: &Limit) -> HashMap<(usize, usize), usize> {
+    (0..product.presheaf.number_of_sections())
+        .map(|section| {
+            let left = product.projections[0].image(section);
+            let right = product.projections[1].image(section);
+            ((left, right), section)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cat::CategoryClassSet;
+    use crate::psh::PresheafSet;
+
+    fn monoids() -> Vec<Category> {
+        let mut monoids = Vec::new();
+        for n in 1..=3 {
+            let set = CategoryClassSet::new(1, vec![0; n - 1], vec![0; n - 1]);
+            cursor!(monoid in &set => {
+                monoids.push(monoid.clone());
+            });
+        }
+        monoids
+    }
+
+    fn acts(monoid: &Category) -> Vec<Presheaf> {
+        let mut acts = Vec::new();
+        for m in 1..=2 {
+            let pi = vec![0; m];
+            let set = PresheafSet::new(monoid, &pi);
+            cursor!(act in &set => {
+                acts.push(act.clone());
+            });
+        }
+        acts
+    }
+
+    fn morphisms(
+        category: &Category,
+        source: &Presheaf,
+        target: &Presheaf,
+    ) -> Vec<PresheafMorphism> {
+        let mut morphisms = Vec::new();
+        let set = PresheafMorphismSet::new(category, source, target);
+        cursor!(phi in &set => {
+            morphisms.push(phi.clone());
+        });
+        morphisms
+    }
+
+    #[test]
+    fn hom_into_the_exponential_counts_hom_from_the_product() {
+        for monoid in monoids() {
+            let acts = acts(&monoid);
+            for p in &acts {
+                for q in &acts {
+                    let exponential = Presheaf::exponential(&monoid, p, q);
+                    assert!(exponential.presheaf.validate(&monoid).is_ok());
+                    for r in &acts {
+                        let product = Presheaf::binary_product(&monoid, r, p);
+                        assert_eq!(
+                            morphisms(&monoid, &product.presheaf, q).len(),
+                            morphisms(&monoid, r, &exponential.presheaf).len()
+                        );
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn evaluation_after_currying_gives_back_the_morphism() {
+        for monoid in monoids() {
+            let acts = acts(&monoid);
+            for p in &acts {
+                for q in &acts {
+                    let exponential = Presheaf::exponential(&monoid, p, q);
+                    let with_base = Presheaf::binary_product(
+                        &monoid,
+                        &exponential.presheaf,
+                        p,
+                    );
+                    let pairs = pair_index(&with_base);
+                    for r in &acts {
+                        let product = Presheaf::binary_product(&monoid, r, p);
+                        for phi in morphisms(&monoid, &product.presheaf, q) {
+                            let curried = exponential.curry(&monoid, r, &phi);
+                            for x in 0..product.presheaf.number_of_sections() {
+                                let r = product.projections[0].image(x);
+                                let p = product.projections[1].image(x);
+                                let pair = pairs[&(curried.image(r), p)];
+                                assert_eq!(
+                                    exponential.evaluation.image(pair),
+                                    phi.image(x)
+                                );
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,476 @@
+use super::Presheaf;
+use crate::cat::{Category, UNDEFINED};
+use crate::set::{Backtrack, Set, Variable};
+
+#[derive(Debug, PartialEq)]
+pub enum FibreSizesError {
+    WrongNumberOfSizes { expected: usize, found: usize },
+}
+
+impl std::fmt::Display for FibreSizesError {
+    fn fmt(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            FibreSizesError::WrongNumberOfSizes { expected, found } => {
+                write!(
+                    formatter,
+                    "Wrong number of fibre sizes: expected={} and found={}",
+                    expected, found
+                )
+            }
+        }
+    }
+}
+
+impl std::error::Error for FibreSizesError {}
+
+// Enumerates the presheaves whose fibre over each object has a given size.
+// Sections are sorted by fibre, so the fibre over `object` consists of the
+// sections `sizes[0] + ... + sizes[object - 1]` onwards, and each action
+// s·f only runs through the fibre over the source of f. The actions are
+// assigned section by section, and a partial assignment is abandoned as soon
+// as it breaks (s·f)·g = s·(f∘g) on the cells decided so far.
+#[derive(Clone, Debug)]
+pub struct FibredPresheafSet<'a> {
+    category: &'a Category,
+    sizes: Vec<usize>,
+}
+
+impl<'a> FibredPresheafSet<'a> {
+    // Fails unless `sizes` has one entry per object.
+    #[inline(always)]
+    pub fn new(
+        category: &'a Category,
+        sizes: Vec<usize>,
+    ) -> Result<Self, FibreSizesError> {
+        if sizes.len() != category.number_of_objects() {
+            return Err(FibreSizesError::WrongNumberOfSizes {
+                expected: category.number_of_objects(),
+                found: sizes.len(),
+            });
+        }
+        Ok(FibredPresheafSet { category, sizes })
+    }
+}
+
+impl Set<Presheaf> for FibredPresheafSet<'_> {
+    #[inline(always)]
+    fn allocate(&self) -> Variable<Presheaf> {
+        Presheaf::allocate(
+            self.category.number_of_objects(),
+            self.category.number_of_morphisms(),
+            self.sizes.iter().sum(),
+        )
+    }
+
+    #[inline(always)]
+    fn next(&self, current: &mut Presheaf) -> bool {
+        next_actions(self.category, current)
+    }
+
+    #[inline(always)]
+    fn reset(&self, current: &mut Presheaf) -> bool {
+        sort_sections(current, &self.sizes);
+        reset_actions(self.category, current)
+    }
+}
+
+// Enumerates the presheaves with a given total number of sections, running
+// through the fibre sizes from (m, 0, ..., 0) to (0, ..., 0, m) and through
+// the presheaves with those fibre sizes as in `FibredPresheafSet`.
+#[derive(Clone, Debug)]
+pub struct SizedPresheafSet<'a> {
+    category: &'a Category,
+    number_of_sections: usize,
+}
+
+impl<'a> SizedPresheafSet<'a> {
+    #[inline(always)]
+    pub fn new(category: &'a Category, number_of_sections: usize) -> Self {
+        SizedPresheafSet {
+            category,
+            number_of_sections,
+        }
+    }
+}
+
+impl Set<Presheaf> for SizedPresheafSet<'_> {
+    #[inline(always)]
+    fn allocate(&self) -> Variable<Presheaf> {
+        Presheaf::allocate(
+            self.category.number_of_objects(),
+            self.category.number_of_morphisms(),
+            self.number_of_sections,
+        )
+    }
+
+    #[inline(always)]
+    fn next(&self, current: &mut Presheaf) -> bool {
+        if next_actions(self.category, current) {
+            return true;
+        }
+        let mut sizes = fibre_sizes(current);
+        while next_sizes(&mut sizes) {
+            sort_sections(current, &sizes);
+            if reset_actions(self.category, current) {
+                return true;
+            }
+        }
+        false
+    }
+
+    #[inline(always)]
+    fn reset(&self, current: &mut Presheaf) -> bool {
+        let objects = self.category.number_of_objects();
+        if objects == 0 {
+            return self.number_of_sections == 0;
+        }
+        let mut sizes = vec![0; objects];
+        sizes[0] = self.number_of_sections;
+        loop {
+            sort_sections(current, &sizes);
+            if reset_actions(self.category, current) {
+                return true;
+            }
+            if !next_sizes(&mut sizes) {
+                return false;
+            }
+        }
+    }
+}
+
+// Lays the sections out fibre by fibre.
+#[inline(always)]
+fn sort_sections(current: &mut Presheaf, sizes: &[usize]) {
+    let mut s = 0;
+    for (object, &size) in sizes.iter().enumerate() {
+        for _ in 0..size {
+            current.pi[s] = object;
+            s += 1;
+        }
+    }
+}
+
+#[inline(always)]
+fn fibre_sizes(current: &Presheaf) -> Vec<usize> {
+    let mut sizes = vec![0; current.number_of_objects()];
+    for &object in &current.pi {
+        sizes[object] += 1;
+    }
+    sizes
+}
+
+// The next way of distributing the sections over the fibres, in reverse
+// lexicographic order.
+#[inline(always)]
+fn next_sizes(sizes: &mut [usize]) -> bool {
+    let objects = sizes.len();
+    for i in (0..objects.saturating_sub(1)).rev() {
+        if sizes[i] > 0 {
+            sizes[i] -= 1;
+            let rest: usize = sizes[i + 1..].iter().sum();
+            sizes[i + 1..].fill(0);
+            sizes[i + 1] = rest + 1;
+            return true;
+        }
+    }
+    false
+}
+
+// The sections over `object`, for sections sorted by fibre.
+#[inline(always)]
+fn fibre_range(current: &Presheaf, object: usize) -> (usize, usize) {
+    let start = current.pi.partition_point(|&o| o < object);
+    let end = current.pi.partition_point(|&o| o <= object);
+    (start, end)
+}
+
+// The search runs through the cells (s, f) of the action table ordered by
+// section and then by non-identity morphism, so the cell (s, f) is at
+// position s·k + (f - n) for k non-identity morphisms. Cells with
+// pi(s) != target(f) only take the value UNDEFINED; the others run through
+// the fibre over source(f).
+#[inline(always)]
+fn cell(category: &Category, position: usize) -> (usize, usize) {
+    let n = category.number_of_objects();
+    let k = category.number_of_morphisms() - n;
+    (position / k, n + position % k)
+}
+
+#[inline(always)]
+fn index(category: &Category, current: &Presheaf, s: usize, f: usize) -> usize {
+    s + (f - category.number_of_objects()) * current.number_of_sections()
+}
+
+// The number of candidates for the cell (s, f), and the value of its
+// `candidate`-th one.
+#[inline(always)]
+fn candidates(
+    category: &Category,
+    current: &Presheaf,
+    s: usize,
+    f: usize,
+) -> usize {
+    if current.pi(s) != category.target(f) {
+        return 1;
+    }
+    let (start, end) = fibre_range(current, category.source(f));
+    end - start
+}
+
+#[inline(always)]
+fn candidate_value(
+    category: &Category,
+    current: &Presheaf,
+    s: usize,
+    f: usize,
+    candidate: usize,
+) -> usize {
+    if current.pi(s) != category.target(f) {
+        return UNDEFINED;
+    }
+    let (start, _) = fibre_range(current, category.source(f));
+    start + candidate
+}
+
+// The position of the current value of the cell (s, f) among its candidates.
+#[inline(always)]
+fn candidate(
+    category: &Category,
+    current: &Presheaf,
+    s: usize,
+    f: usize,
+) -> usize {
+    if current.pi(s) != category.target(f) {
+        return 0;
+    }
+    let (start, _) = fibre_range(current, category.source(f));
+    current.action[index(category, current, s, f)] - start
+}
+
+// The action s·f, for s over the target of f, if it is already decided at
+// `position`.
+#[inline(always)]
+fn value(
+    category: &Category,
+    current: &Presheaf,
+    position: usize,
+    s: usize,
+    f: usize,
+) -> Option<usize> {
+    let n = category.number_of_objects();
+    if f < n {
+        return Some(s);
+    }
+    let k = category.number_of_morphisms() - n;
+    if s * k + (f - n) <= position {
+        Some(current.action[index(category, current, s, f)])
+    } else {
+        None
+    }
+}
+
+// Checks (t·g)·h = t·(g∘h) for every composable triple in which the cell
+// (s, f) at `position` takes part and whose other cells are decided.
+#[inline(always)]
+fn validate_cell(
+    category: &Category,
+    current: &Presheaf,
+    position: usize,
+    s: usize,
+    f: usize,
+) -> bool {
+    let n = category.number_of_objects();
+    let m = category.number_of_morphisms();
+    if current.pi(s) != category.target(f) {
+        return true;
+    }
+    let s_f = current.action[index(category, current, s, f)];
+    let decided = |t, g| value(category, current, position, t, g);
+
+    // (s·f)·g = s·(f∘g)
+    for g in n..m {
+        let Some(fg) = category.composition(f, g) else {
+            continue;
+        };
+        let left = decided(s_f, g);
+        let right = decided(s, fg);
+        if left.is_some() && right.is_some() && left != right {
+            return false;
+        }
+    }
+
+    // (t·g)·f = t·(g∘f) with t·g = s
+    for t in 0..=s {
+        for g in n..m {
+            if current.pi(t) != category.target(g) || decided(t, g) != Some(s) {
+                continue;
+            }
+            let Some(gf) = category.composition(g, f) else {
+                continue;
+            };
+            let right = decided(t, gf);
+            if right.is_some() && right != Some(s_f) {
+                return false;
+            }
+        }
+    }
+
+    // (s·g)·h = s·f with g∘h = f
+    for g in n..m {
+        if category.target(g) != category.target(f) {
+            continue;
+        }
+        for h in n..m {
+            if category.composition(g, h) != Some(f) {
+                continue;
+            }
+            let left = decided(s, g).and_then(|s_g| decided(s_g, h));
+            if left.is_some() && left != Some(s_f) {
+                return false;
+            }
+        }
+    }
+    true
+}
+
+// The search through the action tables of the presheaves whose sections
+// are laid out as in `current`, with the cells as slots.
+struct Actions<'a> {
+    category: &'a Category,
+}
+
+impl Backtrack<Presheaf> for Actions<'_> {
+    #[inline(always)]
+    fn slots(&self, current: &Presheaf) -> usize {
+        current.action.len()
+    }
+
+    #[inline(always)]
+    fn candidates(&self, current: &Presheaf, position: usize) -> usize {
+        let (s, f) = cell(self.category, position);
+        candidates(self.category, current, s, f)
+    }
+
+    #[inline(always)]
+    fn assign(
+        &self,
+        current: &mut Presheaf,
+        position: usize,
+        candidate: usize,
+    ) -> bool {
+        let (s, f) = cell(self.category, position);
+        let index = index(self.category, current, s, f);
+        current.action[index] =
+            candidate_value(self.category, current, s, f, candidate);
+        validate_cell(self.category, current, position, s, f)
+    }
+
+    #[inline(always)]
+    fn candidate(&self, current: &Presheaf, position: usize) -> usize {
+        let (s, f) = cell(self.category, position);
+        candidate(self.category, current, s, f)
+    }
+}
+
+#[inline(always)]
+fn reset_actions(category: &Category, current: &mut Presheaf) -> bool {
+    Actions { category }.search_first(current)
+}
+
+#[inline(always)]
+fn next_actions(category: &Category, current: &mut Presheaf) -> bool {
+    Actions { category }.search_next(current)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cat::SizedCategoryClassSet;
+    use crate::cursor;
+    use crate::set::{AtomSet, ProductSet};
+    use std::collections::HashSet;
+
+    // The action tables of the presheaves in `set`, checking that each one
+    // validates and comes up only once.
+    fn actions(
+        category: &Category,
+        set: &impl Set<Presheaf>,
+    ) -> HashSet<(Vec<usize>, Vec<usize>)> {
+        let mut actions = HashSet::new();
+        cursor!(presheaf in set => {
+            assert!(presheaf.validate(category).is_ok());
+            let new =
+                actions.insert((presheaf.pi.clone(), presheaf.action.clone()));
+            assert!(new);
+        });
+        actions
+    }
+
+    // Every action table with sections over `pi` that validates, trying all
+    // sections in every composable cell.
+    fn brute_force(
+        category: &Category,
+        pi: &[usize],
+    ) -> HashSet<(Vec<usize>, Vec<usize>)> {
+        let n = category.number_of_objects();
+        let k = category.number_of_morphisms() - n;
+        let cells: Vec<usize> = (0..pi.len() * k)
+            .filter(|&i| pi[i % pi.len()] == category.target(n + i / pi.len()))
+            .collect();
+        let tables =
+            ProductSet::new(&vec![AtomSet::new(pi.len()); cells.len()]);
+        let mut actions = HashSet::new();
+        cursor!(entries in &tables => {
+            let mut action = vec![UNDEFINED; pi.len() * k];
+            for (&cell, &entry) in cells.iter().zip(entries) {
+                action[cell] = entry;
+            }
+            let presheaf = Presheaf::new(category, pi.to_vec(), action);
+            if presheaf.validate(category).is_ok() {
+                actions.insert((presheaf.pi, presheaf.action));
+            }
+        });
+        actions
+    }
+
+    #[test]
+    fn agrees_with_brute_force() {
+        for m in 1..=4 {
+            cursor!((_, category) in &SizedCategoryClassSet::new(m, None) => {
+                let n = category.number_of_objects();
+                let mut totals = [0; 4];
+                let vectors = ProductSet::new(&vec![AtomSet::new(4); n]);
+                cursor!(sizes in &vectors => {
+                    let total: usize = sizes.iter().sum();
+                    if total <= 3 {
+                        let pi: Vec<usize> = (0..n)
+                            .flat_map(|object| vec![object; sizes[object]])
+                            .collect();
+                        let fibred =
+                            FibredPresheafSet::new(category, sizes.clone())
+                                .unwrap();
+                        let expected = brute_force(category, &pi);
+                        assert_eq!(actions(category, &fibred), expected);
+                        totals[total] += expected.len();
+                    }
+                });
+                for (total, &count) in totals.iter().enumerate() {
+                    let sized = SizedPresheafSet::new(category, total);
+                    assert_eq!(actions(category, &sized).len(), count);
+                }
+            });
+        }
+    }
+
+    #[test]
+    fn rejects_the_wrong_number_of_sizes() {
+        let monoid = Category::new(1, vec![], vec![], vec![]);
+        assert_eq!(
+            FibredPresheafSet::new(&monoid, vec![1, 2]).err(),
+            Some(FibreSizesError::WrongNumberOfSizes {
+                expected: 1,
+                found: 2
+            })
+        );
+    }
+}
@@ -0,0 +1,243 @@
+use super::{FibreSizesError, FibredPresheafSet, Presheaf, PresheafMorphism};
+use crate::cat::Category;
+use crate::set::{Filter, Set, Variable};
+use std::cmp::Ordering;
+
+// Relabellings of the sections are built one new label at a time, choosing
+// for new label s (in increasing order) an unused old section over pi(s).
+// Comparing the relabelled action table with the original one cell by cell
+// decides most branches long before the relabelling is complete.
+struct Relabelling<'a> {
+    category: &'a Category,
+    presheaf: &'a Presheaf,
+    fibres: Vec<Vec<usize>>,
+    forward: Vec<Option<usize>>,
+    backward: Vec<usize>,
+    counts: Vec<usize>,
+}
+
+impl<'a> Relabelling<'a> {
+    fn new(category: &'a Category, presheaf: &'a Presheaf) -> Self {
+        let fibres = (0..category.number_of_objects())
+            .map(|object| presheaf.fibre(object))
+            .collect();
+        Relabelling {
+            category,
+            presheaf,
+            fibres,
+            forward: vec![None; presheaf.number_of_sections()],
+            backward: vec![],
+            counts: vec![0; category.number_of_objects()],
+        }
+    }
+
+    // Compares the rows of the relabelled table that belong to the labels
+    // given out so far with those of the original table. An old section
+    // without a label yet will get at least the next free label of its
+    // fibre; if that is not enough to decide, the result is None.
+    fn compare(&self) -> Option<Ordering> {
+        let n = self.category.number_of_objects();
+        for s in 0..self.backward.len() {
+            for f in n..self.category.number_of_morphisms() {
+                let Some(original) = self.presheaf.action(s, f) else {
+                    continue;
+                };
+                let t = self
+                    .presheaf
+                    .action(self.backward[s], f)
+                    .expect("relabellings preserve fibres");
+                let relabelled = match self.forward[t] {
+                    Some(label) => label,
+                    None => {
+                        let object = self.presheaf.pi(t);
+                        let bound = self.fibres[object][self.counts[object]];
+                        if bound > original {
+                            return Some(Ordering::Greater);
+                        }
+                        return None;
+                    }
+                };
+                match relabelled.cmp(&original) {
+                    Ordering::Equal => {}
+                    ordering => return Some(ordering),
+                }
+            }
+        }
+        Some(Ordering::Equal)
+    }
+
+    // Extends the relabelling in every way that could still give back the
+    // original table, collecting the automorphisms. Returns false as soon as
+    // a relabelling gives a smaller table if `canonical` is set.
+    fn extend(
+        &mut self,
+        canonical: bool,
+        automorphisms: &mut Vec<PresheafMorphism>,
+    ) -> bool {
+        let s = self.backward.len();
+        if s == self.presheaf.number_of_sections() {
+            automorphisms.push(PresheafMorphism::new(
+                self.forward.iter().map(|label| label.unwrap()).collect(),
+            ));
+            return true;
+        }
+        let object = self.presheaf.pi(s);
+        for i in 0..self.fibres[object].len() {
+            let old = self.fibres[object][i];
+            if self.forward[old].is_some() {
+                continue;
+            }
+            self.forward[old] = Some(s);
+            self.backward.push(old);
+            self.counts[object] += 1;
+            let proceed = match self.compare() {
+                Some(Ordering::Less) => !canonical,
+                Some(Ordering::Greater) => true,
+                _ => self.extend(canonical, automorphisms),
+            };
+            self.counts[object] -= 1;
+            self.backward.pop();
+            self.forward[old] = None;
+            if !proceed {
+                return false;
+            }
+        }
+        true
+    }
+}
+
+impl Presheaf {
+    // The automorphisms of the presheaf, as bijective morphisms onto itself.
+    pub fn automorphisms(&self, category: &Category) -> Vec<PresheafMorphism> {
+        let mut automorphisms = vec![];
+        Relabelling::new(category, self).extend(false, &mut automorphisms);
+        automorphisms
+    }
+
+    // Whether no fibre-preserving relabelling of the sections gives a
+    // lexicographically smaller action table.
+    pub fn is_canonical(&self, category: &Category) -> bool {
+        Relabelling::new(category, self).extend(true, &mut vec![])
+    }
+}
+
+// Enumerates the presheaves with the given fibre sizes up to isomorphism,
+// keeping of every orbit under relabelling of the sections only the
+// presheaf with the lexicographically smallest action table. Summing
+// sizes[0]!·...·sizes[n-1]! / |Aut(P)| over the classes gives back the
+// number of presheaves found by `FibredPresheafSet`.
+#[derive(Clone, Debug)]
+pub struct PresheafClassSet<'a> {
+    category: &'a Category,
+    presheaves: FibredPresheafSet<'a>,
+}
+
+impl<'a> PresheafClassSet<'a> {
+    // Fails unless `sizes` has one entry per object.
+    #[inline(always)]
+    pub fn new(
+        category: &'a Category,
+        sizes: Vec<usize>,
+    ) -> Result<Self, FibreSizesError> {
+        Ok(PresheafClassSet {
+            category,
+            presheaves: FibredPresheafSet::new(category, sizes)?,
+        })
+    }
+}
+
+impl Filter<Presheaf> for PresheafClassSet<'_> {
+    #[inline(always)]
+    fn inner_reset(&self, current: &mut Presheaf) -> bool {
+        self.presheaves.reset(current)
+    }
+
+    #[inline(always)]
+    fn inner_next(&self, current: &mut Presheaf) -> bool {
+        self.presheaves.next(current)
+    }
+
+    #[inline(always)]
+    fn accepts(&self, current: &Presheaf) -> bool {
+        current.is_canonical(self.category)
+    }
+}
+
+impl Set<Presheaf> for PresheafClassSet<'_> {
+    #[inline(always)]
+    fn allocate(&self) -> Variable<Presheaf> {
+        self.presheaves.allocate()
+    }
+
+    #[inline(always)]
+    fn next(&self, current: &mut Presheaf) -> bool {
+        self.filter_next(current)
+    }
+
+    #[inline(always)]
+    fn reset(&self, current: &mut Presheaf) -> bool {
+        self.filter_first(current)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cat::{CategoryClassSet, SizedCategoryClassSet};
+    use crate::cursor;
+    use crate::set::{AtomSet, ProductSet};
+
+    // Orbit-stabilizer: the relabellings within the fibres act on the
+    // presheaves, with the automorphisms as stabilizers.
+    fn assert_orbits_add_up(category: &Category, sizes: &[usize]) {
+        let presheaves =
+            FibredPresheafSet::new(category, sizes.to_vec()).unwrap();
+        let mut count = 0;
+        cursor!(_ in &presheaves => {
+            count += 1;
+        });
+        let relabellings: usize = sizes
+            .iter()
+            .map(|&size| (1..=size).product::<usize>())
+            .product();
+        let classes = PresheafClassSet::new(category, sizes.to_vec()).unwrap();
+        let mut orbits = 0;
+        cursor!(presheaf in &classes => {
+            assert!(presheaf.is_canonical(category));
+            let automorphisms = presheaf.automorphisms(category);
+            for phi in &automorphisms {
+                assert!(phi.validate(category, presheaf, presheaf).is_ok());
+            }
+            orbits += relabellings / automorphisms.len();
+        });
+        assert_eq!(orbits, count);
+    }
+
+    #[test]
+    fn orbits_add_up_for_monoid_acts() {
+        for n in 1..=3 {
+            let monoids =
+                CategoryClassSet::new(1, vec![0; n - 1], vec![0; n - 1]);
+            cursor!(monoid in &monoids => {
+                for m in 0..=4 {
+                    assert_orbits_add_up(monoid, &[m]);
+                }
+            });
+        }
+    }
+
+    #[test]
+    fn orbits_add_up_on_several_objects() {
+        for m in 2..=4 {
+            cursor!((_, category) in &SizedCategoryClassSet::new(m, None) => {
+                let n = category.number_of_objects();
+                let vectors = ProductSet::new(&vec![AtomSet::new(4); n]);
+                cursor!(sizes in &vectors => {
+                    if n > 1 && sizes.iter().sum::<usize>() <= 3 {
+                        assert_orbits_add_up(category, sizes);
+                    }
+                });
+            });
+        }
+    }
+}
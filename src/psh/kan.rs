@@ -0,0 +1,246 @@
+use super::colimits::{find, number_classes};
+use super::yoneda::morphisms_into;
+use super::{Presheaf, PresheafMorphism, PresheafMorphismSet};
+use crate::cat::{Category, Functor};
+use crate::cursor;
+use crate::set::Set;
+use std::collections::HashMap;
+
+// Presheaves along a functor F: C → D, with `self` a presheaf on D for the
+// restriction and on C for the Kan extensions.
+impl Presheaf {
+    // The restriction F*Q = Q∘F. Its sections over c are the pairs (c, q)
+    // with q over F(c), in lexicographic order, and (c, q)·f = (c', q·F(f))
+    // for f: c' → c.
+    pub fn restriction(
+        &self,
+        domain: &Category,
+        functor: &Functor,
+    ) -> Presheaf {
+        let pairs = restriction_pairs(domain, self, functor);
+        let index = positions(&pairs);
+        let pi = pairs.iter().map(|&(c, _)| c).collect();
+        Presheaf::from_fn(domain, pi, |s, f| {
+            let (_, q) = pairs[s];
+            let q_f = self.action(q, functor.morphism(f))?;
+            Some(index[&(domain.source(f), q_f)])
+        })
+    }
+
+    // The left Kan extension Σ_F P, the left adjoint of F*. Over d it is the
+    // colimit of P over the pairs (c, u: d → F(c)), i.e. the pairs (p, u)
+    // with u: d → F(pi(p)) modulo (p·g, u) ~ (p, F(g)∘u). The classes are
+    // ordered by their first pair, and the action is (p, u)·h = (p, u∘h).
+    pub fn left_kan_extension(
+        &self,
+        domain: &Category,
+        codomain: &Category,
+        functor: &Functor,
+    ) -> Presheaf {
+        let mut pairs = vec![];
+        for p in 0..self.number_of_sections() {
+            for u in morphisms_into(codomain, functor.object(self.pi(p))) {
+                pairs.push((p, u));
+            }
+        }
+        let index = positions(&pairs);
+
+        // the relation is already compatible with the action, so its
+        // equivalence closure is a congruence
+        let mut parent: Vec<usize> = (0..pairs.len()).collect();
+        for p in 0..self.number_of_sections() {
+            for g in domain.number_of_objects()..domain.number_of_morphisms() {
+                if domain.target(g) != self.pi(p) {
+                    continue;
+                }
+                let image = functor.morphism(g);
+                for u in morphisms_into(codomain, codomain.source(image)) {
+                    let p_g = self.action(p, g).expect("p lies over target(g)");
+                    let composite = codomain
+                        .composition(image, u)
+                        .expect("u lies over the source of F(g)");
+                    let left = index[&(p_g, u)];
+                    let right = index[&(p, composite)];
+                    let root_left = find(&mut parent, left);
+                    let root_right = find(&mut parent, right);
+                    parent[usize::max(root_left, root_right)] =
+                        usize::min(root_left, root_right);
+                }
+            }
+        }
+
+        let (classes, representatives) = number_classes(&mut parent);
+        let pi = representatives
+            .iter()
+            .map(|&i| codomain.source(pairs[i].1))
+            .collect();
+        Presheaf::from_fn(codomain, pi, |class, h| {
+            let (p, u) = pairs[representatives[class]];
+            Some(classes[index[&(p, codomain.composition(u, h)?)]])
+        })
+    }
+
+    // The right Kan extension Π_F P, the right adjoint of F*. Over d it is
+    // the end of P over the pairs (c, u: F(c) → d), i.e. Hom(F*y(d), P),
+    // with the transformations over d in the order of `PresheafMorphismSet`.
+    // A transformation α over d acts by h: d' → d as (α·h)(c, u) =
+    // α(c, h∘u).
+    pub fn right_kan_extension(
+        &self,
+        domain: &Category,
+        codomain: &Category,
+        functor: &Functor,
+    ) -> Presheaf {
+        let representables: Vec<Presheaf> = (0..codomain.number_of_objects())
+            .map(|d| Presheaf::representable(codomain, d))
+            .collect();
+        let pairs: Vec<_> = representables
+            .iter()
+            .map(|representable| {
+                restriction_pairs(domain, representable, functor)
+            })
+            .collect();
+        let indices: Vec<_> =
+            pairs.iter().map(|pairs| positions(pairs)).collect();
+
+        let mut transformations: Vec<(usize, PresheafMorphism)> = vec![];
+        for (d, representable) in representables.iter().enumerate() {
+            let restriction = representable.restriction(domain, functor);
+            let set = PresheafMorphismSet::new(domain, &restriction, self);
+            cursor!(alpha in &set => {
+                transformations.push((d, alpha.clone()));
+            });
+        }
+        let index: HashMap<_, _> = transformations
+            .iter()
+            .enumerate()
+            .map(|(position, transformation)| {
+                (transformation.clone(), position)
+            })
+            .collect();
+
+        // u: F(c) → d is section `sections[u]` of y(d)
+        let mut sections = vec![0; codomain.number_of_morphisms()];
+        for d in 0..codomain.number_of_objects() {
+            for (section, u) in
+                morphisms_into(codomain, d).into_iter().enumerate()
+            {
+                sections[u] = section;
+            }
+        }
+
+        let pi = transformations.iter().map(|&(d, _)| d).collect();
+        Presheaf::from_fn(codomain, pi, |s, h| {
+            let (d, alpha) = &transformations[s];
+            let source = codomain.source(h);
+            let morphisms = morphisms_into(codomain, source);
+            let map = pairs[source]
+                .iter()
+                .map(|&(c, u)| {
+                    let composite = codomain.composition(h, morphisms[u])?;
+                    Some(alpha.image(indices[*d][&(c, sections[composite])]))
+                })
+                .collect::<Option<_>>()?;
+            Some(index[&(source, PresheafMorphism::new(map))])
+        })
+    }
+}
+
+// The sections (c, q) of F*Q in order.
+fn restriction_pairs(
+    domain: &Category,
+    presheaf: &Presheaf,
+    functor: &Functor,
+) -> Vec<(usize, usize)> {
+    let mut pairs = vec![];
+    for c in 0..domain.number_of_objects() {
+        for q in presheaf.fibre(functor.object(c)) {
+            pairs.push((c, q));
+        }
+    }
+    pairs
+}
+
+fn positions(pairs: &[(usize, usize)]) -> HashMap<(usize, usize), usize> {
+    pairs
+        .iter()
+        .enumerate()
+        .map(|(position, &pair)| (pair, position))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cat::{CategoryClassSet, FunctorSet};
+    use crate::psh::PresheafSet;
+
+    fn monoids() -> Vec<Category> {
+        let mut monoids = Vec::new();
+        for n in 1..=3 {
+            let set = CategoryClassSet::new(1, vec![0; n - 1], vec![0; n - 1]);
+            cursor!(monoid in &set => {
+                monoids.push(monoid.clone());
+            });
+        }
+        monoids
+    }
+
+    fn acts(monoid: &Category) -> Vec<Presheaf> {
+        let mut acts = Vec::new();
+        for m in 1..=2 {
+            let pi = vec![0; m];
+            let set = PresheafSet::new(monoid, &pi);
+            cursor!(act in &set => {
+                acts.push(act.clone());
+            });
+        }
+        acts
+    }
+
+    fn count(
+        category: &Category,
+        source: &Presheaf,
+        target: &Presheaf,
+    ) -> usize {
+        let mut count = 0;
+        let set = PresheafMorphismSet::new(category, source, target);
+        cursor!(_ in &set => {
+            count += 1;
+        });
+        count
+    }
+
+    // Hom(Σ_F P, Q) ≅ Hom(P, F*Q) and Hom(F*Q, P) ≅ Hom(Q, Π_F P)
+    #[test]
+    fn kan_extensions_are_adjoint_to_restriction() {
+        let monoids = monoids();
+        for domain in &monoids {
+            let domain_acts = acts(domain);
+            for codomain in &monoids {
+                let codomain_acts = acts(codomain);
+                let functors = FunctorSet::new(domain, codomain);
+                cursor!(functor in &functors => {
+                    for p in &domain_acts {
+                        let left = p.left_kan_extension(domain, codomain, functor);
+                        let right =
+                            p.right_kan_extension(domain, codomain, functor);
+                        assert!(left.validate(codomain).is_ok());
+                        assert!(right.validate(codomain).is_ok());
+                        for q in &codomain_acts {
+                            let restriction = q.restriction(domain, functor);
+                            assert_eq!(
+                                count(codomain, &left, q),
+                                count(domain, p, &restriction)
+                            );
+                            assert_eq!(
+                                count(domain, &restriction, p),
+                                count(codomain, q, &right)
+                            );
+                        }
+                    }
+                });
+            }
+        }
+    }
+}
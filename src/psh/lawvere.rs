@@ -0,0 +1,332 @@
+use super::{
+    GrothendieckTopology, Presheaf, PresheafMorphism, PresheafMorphismError,
+    PresheafMorphismSet, SubobjectClassifier, Subpresheaf,
+};
+use crate::cat::Category;
+use crate::set::{Filter, Set, Variable};
+
+#[derive(Debug, PartialEq)]
+pub enum LawvereTierneyError {
+    NotFibrePreserving { sieve: usize },
+    NotNatural { s: usize, f: usize },
+    NotPreservingTruth { object: usize },
+    NotIdempotent { sieve: usize },
+    NotPreservingMeets { left: usize, right: usize },
+}
+
+impl std::fmt::Display for LawvereTierneyError {
+    fn fmt(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            LawvereTierneyError::NotFibrePreserving { sieve } => {
+                write!(formatter, "Not fibre-preserving: sieve={}", sieve)
+            }
+            LawvereTierneyError::NotNatural { s, f } => {
+                write!(formatter, "Not natural: s={} and f={}", s, f)
+            }
+            LawvereTierneyError::NotPreservingTruth { object } => {
+                write!(formatter, "Truth not preserved: object={}", object)
+            }
+            LawvereTierneyError::NotIdempotent { sieve } => {
+                write!(formatter, "Not idempotent: sieve={}", sieve)
+            }
+            LawvereTierneyError::NotPreservingMeets { left, right } => {
+                write!(
+                    formatter,
+                    "Meet not preserved: left={} and right={}",
+                    left, right
+                )
+            }
+        }
+    }
+}
+
+impl std::error::Error for LawvereTierneyError {}
+
+// A Lawvere–Tierney topology j: Ω → Ω, with sieves referred to by their
+// section of Ω.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct LawvereTierneyTopology {
+    map: PresheafMorphism,
+}
+
+impl LawvereTierneyTopology {
+    #[inline(always)]
+    pub fn new(map: PresheafMorphism) -> Self {
+        LawvereTierneyTopology { map }
+    }
+
+    #[inline(always)]
+    pub fn allocate(classifier: &SubobjectClassifier) -> Variable<Self> {
+        Variable::uninitialized(LawvereTierneyTopology {
+            map: PresheafMorphism::identity(&classifier.presheaf),
+        })
+    }
+
+    #[inline(always)]
+    pub fn map(&self) -> &PresheafMorphism {
+        &self.map
+    }
+
+    #[inline(always)]
+    pub fn validate(
+        &self,
+        category: &Category,
+        classifier: &SubobjectClassifier,
+    ) -> Result<(), LawvereTierneyError> {
+        let omega = &classifier.presheaf;
+        self.map.validate(category, omega, omega).map_err(
+            |error| match error {
+                PresheafMorphismError::NotFibrePreserving { s } => {
+                    LawvereTierneyError::NotFibrePreserving { sieve: s }
+                }
+                PresheafMorphismError::NotNatural { s, f } => {
+                    LawvereTierneyError::NotNatural { s, f }
+                }
+            },
+        )?;
+        self.validate_axioms(category, classifier)
+    }
+
+    // j∘true = true, j∘j = j and j(S ∧ T) = j(S) ∧ j(T), for a map j that is
+    // already known to be a morphism of presheaves.
+    #[inline(always)]
+    fn validate_axioms(
+        &self,
+        category: &Category,
+        classifier: &SubobjectClassifier,
+    ) -> Result<(), LawvereTierneyError> {
+        let omega = &classifier.presheaf;
+        for object in 0..category.number_of_objects() {
+            let truth = classifier.truth.image(object);
+            if self.map.image(truth) != truth {
+                return Err(LawvereTierneyError::NotPreservingTruth { object });
+            }
+        }
+        for sieve in 0..omega.number_of_sections() {
+            let image = self.map.image(sieve);
+            if self.map.image(image) != image {
+                return Err(LawvereTierneyError::NotIdempotent { sieve });
+            }
+        }
+        for left in 0..omega.number_of_sections() {
+            for right in omega.fibre(omega.pi(left)) {
+                let both = meet(classifier, left, right);
+                let images = meet(
+                    classifier,
+                    self.map.image(left),
+                    self.map.image(right),
+                );
+                if self.map.image(both) != images {
+                    return Err(LawvereTierneyError::NotPreservingMeets {
+                        left,
+                        right,
+                    });
+                }
+            }
+        }
+        Ok(())
+    }
+
+    // The closure of a subpresheaf A of P: the sections s with
+    // j(χ_A(s)) = true.
+    pub fn closure(
+        &self,
+        category: &Category,
+        classifier: &SubobjectClassifier,
+        presheaf: &Presheaf,
+        subpresheaf: &Subpresheaf,
+    ) -> Subpresheaf {
+        let chi = classifier.classify(category, presheaf, subpresheaf);
+        Subpresheaf::new(
+            (0..presheaf.number_of_sections())
+                .map(|s| {
+                    let truth = classifier.truth.image(presheaf.pi(s));
+                    self.map.image(chi.image(s)) == truth
+                })
+                .collect(),
+        )
+    }
+
+    // The corresponding Grothendieck topology: the sieves S with
+    // j(S) = true.
+    pub fn to_grothendieck(
+        &self,
+        classifier: &SubobjectClassifier,
+    ) -> GrothendieckTopology {
+        let omega = &classifier.presheaf;
+        GrothendieckTopology::new(Subpresheaf::new(
+            (0..omega.number_of_sections())
+                .map(|sieve| {
+                    self.map.image(sieve)
+                        == classifier.truth.image(omega.pi(sieve))
+                })
+                .collect(),
+        ))
+    }
+}
+
+impl GrothendieckTopology {
+    // The corresponding Lawvere–Tierney topology, sending a sieve S on c to
+    // {f : f*S covers}.
+    pub fn to_lawvere_tierney(
+        &self,
+        category: &Category,
+        classifier: &SubobjectClassifier,
+    ) -> LawvereTierneyTopology {
+        let omega = &classifier.presheaf;
+        LawvereTierneyTopology::new(PresheafMorphism::new(
+            (0..omega.number_of_sections())
+                .map(|sieve| {
+                    let object = omega.pi(sieve);
+                    let closure: Vec<bool> = (0..category
+                        .number_of_morphisms())
+                        .map(|f| {
+                            omega.action(sieve, f).is_some_and(|pulled_back| {
+                                self.covers(pulled_back)
+                            })
+                        })
+                        .collect();
+                    classifier.section(object, &closure)
+                })
+                .collect(),
+        ))
+    }
+}
+
+// The intersection of two sieves on the same object.
+fn meet(classifier: &SubobjectClassifier, left: usize, right: usize) -> usize {
+    let intersection: Vec<bool> = classifier
+        .sieve(left)
+        .iter()
+        .zip(classifier.sieve(right))
+        .map(|(&a, &b)| a && b)
+        .collect();
+    classifier.section(classifier.presheaf.pi(left), &intersection)
+}
+
+// Enumerates all Lawvere–Tierney topologies, by running through the
+// endomorphisms of Ω and keeping those that satisfy the axioms.
+#[derive(Clone, Debug)]
+pub struct LawvereTierneyTopologySet<'a> {
+    category: &'a Category,
+    classifier: &'a SubobjectClassifier,
+    endomorphisms: PresheafMorphismSet<'a>,
+}
+
+impl<'a> LawvereTierneyTopologySet<'a> {
+    #[inline(always)]
+    pub fn new(
+        category: &'a Category,
+        classifier: &'a SubobjectClassifier,
+    ) -> Self {
+        let omega = &classifier.presheaf;
+        LawvereTierneyTopologySet {
+            category,
+            classifier,
+            endomorphisms: PresheafMorphismSet::new(category, omega, omega),
+        }
+    }
+}
+
+impl Filter<LawvereTierneyTopology> for LawvereTierneyTopologySet<'_> {
+    #[inline(always)]
+    fn inner_reset(&self, current: &mut LawvereTierneyTopology) -> bool {
+        self.endomorphisms.reset(&mut current.map)
+    }
+
+    #[inline(always)]
+    fn inner_next(&self, current: &mut LawvereTierneyTopology) -> bool {
+        self.endomorphisms.next(&mut current.map)
+    }
+
+    #[inline(always)]
+    fn accepts(&self, current: &LawvereTierneyTopology) -> bool {
+        current
+            .validate_axioms(self.category, self.classifier)
+            .is_ok()
+    }
+}
+
+impl Set<LawvereTierneyTopology> for LawvereTierneyTopologySet<'_> {
+    #[inline(always)]
+    fn allocate(&self) -> Variable<LawvereTierneyTopology> {
+        LawvereTierneyTopology::allocate(self.classifier)
+    }
+
+    #[inline(always)]
+    fn next(&self, current: &mut LawvereTierneyTopology) -> bool {
+        self.filter_next(current)
+    }
+
+    #[inline(always)]
+    fn reset(&self, current: &mut LawvereTierneyTopology) -> bool {
+        self.filter_first(current)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cat::{CategoryClassSet, UNDEFINED};
+    use crate::cursor;
+    use crate::psh::GrothendieckTopologySet;
+    use std::collections::HashSet;
+
+    fn categories() -> Vec<Category> {
+        let mut categories = Vec::new();
+        for n in 1..=3 {
+            let set = CategoryClassSet::new(1, vec![0; n - 1], vec![0; n - 1]);
+            cursor!(monoid in &set => {
+                categories.push(monoid.clone());
+            });
+        }
+        // 0 → 1
+        categories.push(Category::new(2, vec![0], vec![1], vec![UNDEFINED]));
+        categories
+    }
+
+    #[test]
+    fn lawvere_tierney_topologies_are_grothendieck_topologies() {
+        for category in categories() {
+            let classifier = Presheaf::subobject_classifier(&category);
+            let mut grothendieck = HashSet::new();
+            let set = GrothendieckTopologySet::new(&category, &classifier);
+            cursor!(topology in &set => {
+                grothendieck.insert(topology.clone());
+            });
+
+            let mut count = 0;
+            let set = LawvereTierneyTopologySet::new(&category, &classifier);
+            cursor!(j in &set => {
+                count += 1;
+                let topology = j.to_grothendieck(&classifier);
+                assert!(grothendieck.contains(&topology));
+                assert_eq!(
+                    &topology.to_lawvere_tierney(&category, &classifier),
+                    j
+                );
+            });
+            assert_eq!(count, grothendieck.len());
+        }
+    }
+
+    #[test]
+    fn reports_maps_leaving_a_fibre() {
+        // 0 → 1, sending every sieve to the empty sieve on 0
+        let arrow = Category::new(2, vec![0], vec![1], vec![UNDEFINED]);
+        let classifier = Presheaf::subobject_classifier(&arrow);
+        let omega = &classifier.presheaf;
+        let empty = classifier.section(0, &[false; 3]);
+        let j = LawvereTierneyTopology::new(PresheafMorphism::new(vec![
+            empty;
+            omega
+                .number_of_sections(
+                )
+        ]));
+        let sieve = omega.fibre(1)[0];
+        assert_eq!(
+            j.validate(&arrow, &classifier),
+            Err(LawvereTierneyError::NotFibrePreserving { sieve })
+        );
+    }
+}
@@ -0,0 +1,308 @@
+use super::{Presheaf, PresheafMorphism};
+use crate::cat::Category;
+use std::collections::HashMap;
+
+// A limit of a diagram of presheaves: the limit presheaf together with its
+// projections onto the objects of the diagram.
+#[derive(Clone, Debug)]
+pub struct Limit {
+    pub presheaf: Presheaf,
+    pub projections: Vec<PresheafMorphism>,
+}
+
+impl Presheaf {
+    // The terminal presheaf, with exactly one section over every object,
+    // numbered like the objects.
+    pub fn terminal(category: &Category) -> Limit {
+        Presheaf::product(category, &[])
+    }
+
+    pub fn binary_product(
+        category: &Category,
+        left: &Presheaf,
+        right: &Presheaf,
+    ) -> Limit {
+        Presheaf::product(category, &[left, right])
+    }
+
+    // The product of `factors`. Its sections over an object are the tuples
+    // of sections of the factors over that object; they are listed object by
+    // object, with the tuples in lexicographic order.
+    pub fn product(category: &Category, factors: &[&Presheaf]) -> Limit {
+        let mut tuples: Vec<Vec<usize>> = Vec::new();
+        let mut pi = Vec::new();
+        for object in 0..category.number_of_objects() {
+            let fibres: Vec<Vec<usize>> =
+                factors.iter().map(|factor| factor.fibre(object)).collect();
+            if fibres.iter().any(Vec::is_empty) {
+                continue;
+            }
+            let mut positions = vec![0; factors.len()];
+            loop {
+                let tuple = positions
+                    .iter()
+                    .zip(&fibres)
+                    .map(|(&position, fibre)| fibre[position])
+                    .collect();
+                tuples.push(tuple);
+                pi.push(object);
+
+                // advance the last position fastest
+                let mut i = factors.len();
+                while i > 0 {
+                    i -= 1;
+                    positions[i] += 1;
+                    if positions[i] < fibres[i].len() {
+                        break;
+                    }
+                    positions[i] = 0;
+                }
+                if positions.iter().all(|&position| position == 0) {
+                    break;
+                }
+            }
+        }
+
+        let index: HashMap<(usize, &[usize]), usize> = tuples
+            .iter()
+            .zip(&pi)
+            .enumerate()
+            .map(|(section, (tuple, &object))| {
+                ((object, tuple.as_slice()), section)
+            })
+            .collect();
+        let presheaf = Presheaf::from_fn(category, pi, |s, f| {
+            let image: Vec<usize> = tuples[s]
+                .iter()
+                .zip(factors)
+                .map(|(&t, factor)| factor.action(t, f))
+                .collect::<Option<_>>()?;
+            Some(index[&(category.source(f), image.as_slice())])
+        });
+        let projections = (0..factors.len())
+            .map(|i| {
+                PresheafMorphism::new(
+                    tuples.iter().map(|tuple| tuple[i]).collect(),
+                )
+            })
+            .collect();
+        Limit {
+            presheaf,
+            projections,
+        }
+    }
+
+    // The equalizer of two morphisms `first`, `second` out of `source`: the
+    // subpresheaf of sections on which they agree, with its inclusion.
+    pub fn equalizer(
+        category: &Category,
+        source: &Presheaf,
+        first: &PresheafMorphism,
+        second: &PresheafMorphism,
+    ) -> Limit {
+        let members: Vec<bool> = (0..source.number_of_sections())
+            .map(|s| first.image(s) == second.image(s))
+            .collect();
+        let (presheaf, inclusion) = source.restrict(category, &members);
+        Limit {
+            presheaf,
+            projections: vec![inclusion],
+        }
+    }
+
+    // The pullback of `first`: P → R and `second`: Q → R, with its
+    // projections onto P and Q.
+    pub fn pullback(
+        category: &Category,
+        left: &Presheaf,
+        right: &Presheaf,
+        first: &PresheafMorphism,
+        second: &PresheafMorphism,
+    ) -> Limit {
+        let product = Presheaf::binary_product(category, left, right);
+        let equalizer = Presheaf::equalizer(
+            category,
+            &product.presheaf,
+            &first.compose(&product.projections[0]),
+            &second.compose(&product.projections[1]),
+        );
+        let inclusion = &equalizer.projections[0];
+        let projections = product
+            .projections
+            .iter()
+            .map(|projection| projection.compose(inclusion))
+            .collect();
+        Limit {
+            presheaf: equalizer.presheaf,
+            projections,
+        }
+    }
+
+    // The subpresheaf on the sections marked in `members`, which must be
+    // closed under the action, together with its inclusion.
+    pub(crate) fn restrict(
+        &self,
+        category: &Category,
+        members: &[bool],
+    ) -> (Presheaf, PresheafMorphism) {
+        let sections: Vec<usize> = (0..self.number_of_sections())
+            .filter(|&s| members[s])
+            .collect();
+        let mut index = vec![0; self.number_of_sections()];
+        for (section, &s) in sections.iter().enumerate() {
+            index[s] = section;
+        }
+        let pi = sections.iter().map(|&s| self.pi(s)).collect();
+        let presheaf = Presheaf::from_fn(category, pi, |s, f| {
+            Some(index[self.action(sections[s], f)?])
+        });
+        (presheaf, PresheafMorphism::new(sections))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cat::SizedCategoryClassSet;
+    use crate::cursor;
+    use crate::psh::{PresheafMorphismSet, SizedPresheafSet};
+    use crate::set::Set;
+
+    fn categories() -> Vec<Category> {
+        let mut categories = Vec::new();
+        for m in 1..=3 {
+            cursor!((_, category) in &SizedCategoryClassSet::new(m, None) => {
+                categories.push(category.clone());
+            });
+        }
+        categories
+    }
+
+    fn presheaves(category: &Category) -> Vec<Presheaf> {
+        let mut presheaves = Vec::new();
+        for sections in 0..=2 {
+            let set = SizedPresheafSet::new(category, sections);
+            cursor!(presheaf in &set => {
+                presheaves.push(presheaf.clone());
+            });
+        }
+        presheaves
+    }
+
+    fn morphisms(
+        category: &Category,
+        source: &Presheaf,
+        target: &Presheaf,
+    ) -> Vec<PresheafMorphism> {
+        let mut morphisms = Vec::new();
+        let set = PresheafMorphismSet::new(category, source, target);
+        cursor!(phi in &set => {
+            morphisms.push(phi.clone());
+        });
+        morphisms
+    }
+
+    fn pairs<'a, T, U>(
+        left: &'a [T],
+        right: &'a [U],
+    ) -> impl Iterator<Item = (&'a T, &'a U)> {
+        left.iter()
+            .flat_map(move |a| right.iter().map(move |b| (a, b)))
+    }
+
+    fn assert_limit(category: &Category, limit: &Limit, factors: &[&Presheaf]) {
+        assert!(limit.presheaf.validate(category).is_ok());
+        for (projection, factor) in limit.projections.iter().zip(factors) {
+            let valid = projection.validate(category, &limit.presheaf, factor);
+            assert!(valid.is_ok());
+        }
+    }
+
+    #[test]
+    fn terminal_and_products_are_universal() {
+        for category in categories() {
+            let presheaves = presheaves(&category);
+            let terminal = Presheaf::terminal(&category);
+            assert_limit(&category, &terminal, &[]);
+            for r in &presheaves {
+                assert_eq!(
+                    morphisms(&category, r, &terminal.presheaf).len(),
+                    1
+                );
+            }
+            for p in &presheaves {
+                for q in &presheaves {
+                    let product = Presheaf::binary_product(&category, p, q);
+                    assert_limit(&category, &product, &[p, q]);
+                    for r in &presheaves {
+                        assert_eq!(
+                            morphisms(&category, r, &product.presheaf).len(),
+                            morphisms(&category, r, p).len()
+                                * morphisms(&category, r, q).len()
+                        );
+                    }
+                }
+            }
+        }
+    }
+
+    // Hom(R, E) is the set of maps R → P that both morphisms agree on.
+    #[test]
+    fn equalizers_are_universal() {
+        for category in categories() {
+            let presheaves = presheaves(&category);
+            for p in &presheaves {
+                for q in &presheaves {
+                    let parallel = morphisms(&category, p, q);
+                    for (first, second) in pairs(&parallel, &parallel) {
+                        let equalizer =
+                            Presheaf::equalizer(&category, p, first, second);
+                        assert_limit(&category, &equalizer, &[p]);
+                        for r in &presheaves {
+                            let agreeing = morphisms(&category, r, p)
+                                .iter()
+                                .filter(|chi| {
+                                    first.compose(chi) == second.compose(chi)
+                                })
+                                .count();
+                            let maps =
+                                morphisms(&category, r, &equalizer.presheaf);
+                            assert_eq!(maps.len(), agreeing);
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    // Hom(R, P ×_T Q) is the set of pairs R → P, R → Q that agree in T.
+    #[test]
+    fn pullbacks_are_universal() {
+        for category in categories() {
+            let presheaves = presheaves(&category);
+            for (p, q) in pairs(&presheaves, &presheaves) {
+                for t in &presheaves {
+                    let firsts = morphisms(&category, p, t);
+                    let seconds = morphisms(&category, q, t);
+                    for (first, second) in pairs(&firsts, &seconds) {
+                        let pullback =
+                            Presheaf::pullback(&category, p, q, first, second);
+                        assert_limit(&category, &pullback, &[p, q]);
+                        for r in &presheaves {
+                            let lefts = morphisms(&category, r, p);
+                            let rights = morphisms(&category, r, q);
+                            let agreeing = pairs(&lefts, &rights)
+                                .filter(|(a, b)| {
+                                    first.compose(a) == second.compose(b)
+                                })
+                                .count();
+                            let maps =
+                                morphisms(&category, r, &pullback.presheaf);
+                            assert_eq!(maps.len(), agreeing);
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
@@ -1,7 +1,40 @@
-use crate::cat::Category;
+use crate::cat::{Category, UNDEFINED};
 use crate::cursor;
 use crate::set::{AtomSet, Set, Variable};
 
+mod builder;
+mod classifier;
+mod colimits;
+mod elements;
+mod exponential;
+mod fibred;
+mod iso;
+mod kan;
+mod lawvere;
+mod limits;
+mod morphism;
+mod subpresheaf;
+mod topology;
+mod yoneda;
+
+pub use builder::{PresheafBuilder, PresheafBuilderError};
+pub use classifier::SubobjectClassifier;
+pub use colimits::Colimit;
+pub use exponential::Exponential;
+pub use fibred::{FibreSizesError, FibredPresheafSet, SizedPresheafSet};
+pub use iso::PresheafClassSet;
+pub use lawvere::{
+    LawvereTierneyError, LawvereTierneyTopology, LawvereTierneyTopologySet,
+};
+pub use limits::Limit;
+pub use morphism::{
+    PresheafMorphism, PresheafMorphismError, PresheafMorphismSet,
+};
+pub use subpresheaf::{Subpresheaf, SubpresheafError, SubpresheafSet};
+pub use topology::{
+    GrothendieckTopology, GrothendieckTopologySet, TopologyError,
+};
+
 #[derive(Debug, PartialEq)]
 pub enum PresheafError {
     NotWellDefined { s: usize, f: usize },
@@ -49,6 +82,37 @@ impl Presheaf {
         }
     }
 
+    // The presheaf with sections over `pi` whose action on each composable
+    // pair of a section and a non-identity morphism is given by `action`;
+    // all other entries, and those where `action` gives None, are UNDEFINED.
+    pub(crate) fn from_fn<F>(
+        category: &Category,
+        pi: Vec<usize>,
+        mut action: F,
+    ) -> Self
+    where
+        F: FnMut(usize, usize) -> Option<usize>,
+    {
+        let number_of_sections = pi.len();
+        let number_of_objects = category.number_of_objects();
+        let mut table = vec![
+            UNDEFINED;
+            number_of_sections
+                * (category.number_of_morphisms()
+                    - number_of_objects)
+        ];
+        for f in number_of_objects..category.number_of_morphisms() {
+            for (s, &object) in pi.iter().enumerate() {
+                if object == category.target(f) {
+                    let index =
+                        s + (f - number_of_objects) * number_of_sections;
+                    table[index] = action(s, f).unwrap_or(UNDEFINED);
+                }
+            }
+        }
+        Presheaf::new(category, pi, table)
+    }
+
     #[inline(always)]
     pub fn allocate(
         number_of_objects: usize,
@@ -61,7 +125,7 @@ impl Presheaf {
             number_of_morphisms,
             pi: vec![0; number_of_sections],
             action: vec![
-                0;
+                UNDEFINED;
                 number_of_sections
                     * (number_of_morphisms - number_of_objects)
             ],
@@ -93,20 +157,30 @@ impl Presheaf {
         self.pi[index]
     }
 
+    // The sections over `object`, in increasing order.
+    #[inline(always)]
+    pub fn fibre(&self, object: usize) -> Vec<usize> {
+        (0..self.number_of_sections())
+            .filter(|&s| self.pi(s) == object)
+            .collect()
+    }
+
+    // The section s·f, or None if s does not lie over the target of f.
     #[inline(always)]
-    pub fn action(&self, section: usize, morphism: usize) -> usize {
+    pub fn action(&self, section: usize, morphism: usize) -> Option<usize> {
         // identity?
         if morphism < self.number_of_objects() {
-            return section;
+            return (self.pi(section) == morphism).then_some(section);
         }
         let morphism = morphism - self.number_of_objects();
-        self.action[section + morphism * self.number_of_sections()]
+        let entry = self.action[section + morphism * self.number_of_sections()];
+        (entry != UNDEFINED).then_some(entry)
     }
 
     #[inline(always)]
     pub fn validate(&self, category: &Category) -> Result<(), PresheafError> {
-        self.validate_associativity(category)?;
         self.validate_well_definedness(category)?;
+        self.validate_associativity(category)?;
         Ok(())
     }
 
@@ -118,11 +192,16 @@ impl Presheaf {
         let sections = self.sections();
         let morphisms = category.morphisms();
 
+        // (s·f)·g = s·(f∘g) for g: c → a, f: a → b and s over b
         cursor!(s in &sections => {
             cursor!(f in &morphisms => {
                 cursor!(g in &morphisms => {
-                    let left = self.action(self.action(*s, *f), *g);
-                    let right = self.action(*s, category.composition(*g, *f));
+                    let left = self
+                        .action(*s, *f)
+                        .and_then(|s_f| self.action(s_f, *g));
+                    let right = category
+                        .composition(*f, *g)
+                        .and_then(|fg| self.action(*s, fg));
 
                     if left != right {
                         return Err(PresheafError::NonAssociative {
@@ -140,26 +219,23 @@ impl Presheaf {
         &self,
         category: &Category,
     ) -> Result<(), PresheafError> {
-        let sections = self.sections();
-        let morphisms = category.morphisms();
-
-        cursor!(s in &sections => {
-            cursor!(f in &morphisms => {
-                let s_f = self.action(*s, *f);
-                let v = self.pi(s_f);
-                let source_f = category.source(*f);
-                let u = self.pi(*s);
-                let target_f = category.target(*f);
-
-                if  v != source_f {
-                    return Err(PresheafError::NotWellDefined { s: *s, f: *f });
+        // identities act trivially, so we consider the stored entries only
+        let n = category.number_of_objects();
+        for f in n..category.number_of_morphisms() {
+            for s in 0..self.number_of_sections() {
+                let entry =
+                    self.action[s + (f - n) * self.number_of_sections()];
+                let defined = if self.pi(s) == category.target(f) {
+                    entry < self.number_of_sections()
+                        && self.pi(entry) == category.source(f)
+                } else {
+                    entry == UNDEFINED
+                };
+                if !defined {
+                    return Err(PresheafError::NotWellDefined { s, f });
                 }
-
-                if u != target_f && s_f != 0 {
-                    return Err(PresheafError::NotWellDefined { s: *s, f: *f });
-                }
-            });
-        });
+            }
+        }
         Ok(())
     }
 }
@@ -175,6 +251,15 @@ impl<'a> PresheafSet<'a> {
     pub fn new(category: &'a Category, pi: &'a Vec<usize>) -> Self {
         PresheafSet { category, pi }
     }
+
+    // Whether entry `i` of the action table belongs to a section and a
+    // morphism that can be composed.
+    #[inline(always)]
+    fn composable(&self, i: usize) -> bool {
+        let s = i % self.pi.len();
+        let f = i / self.pi.len() + self.category.number_of_objects();
+        self.pi[s] == self.category.target(f)
+    }
 }
 
 impl Set<Presheaf> for PresheafSet<'_> {
@@ -187,18 +272,25 @@ impl Set<Presheaf> for PresheafSet<'_> {
         let presheaf = Presheaf::new(
             self.category,
             self.pi.clone(),
-            vec![0; number_of_sections * number_of_nonidentity_morphisms],
+            vec![
+                UNDEFINED;
+                number_of_sections * number_of_nonidentity_morphisms
+            ],
         );
         Variable::uninitialized(presheaf)
     }
 
     #[inline(always)]
-    fn next<'a>(&self, current: &'a mut Presheaf) -> bool {
+    fn next(&self, current: &mut Presheaf) -> bool {
         let number_of_sections = self.pi.len();
         for i in 0..current.action.len() {
+            // undefined entries stay UNDEFINED
+            if !self.composable(i) {
+                continue;
+            }
             current.action[i] += 1;
             if current.action[i] < number_of_sections {
-                if current.validate(&self.category).is_ok() {
+                if current.validate(self.category).is_ok() {
                     return true;
                 }
                 return self.next(current);
@@ -210,14 +302,12 @@ impl Set<Presheaf> for PresheafSet<'_> {
     }
 
     #[inline(always)]
-    fn reset<'a>(&self, current: &'a mut Presheaf) -> bool {
-        for i in 0..current.pi.len() {
-            current.pi[i] = 0;
-        }
+    fn reset(&self, current: &mut Presheaf) -> bool {
+        current.pi.clone_from(self.pi);
         for i in 0..current.action.len() {
-            current.action[i] = 0;
+            current.action[i] = if self.composable(i) { 0 } else { UNDEFINED };
         }
-        if current.validate(&self.category).is_ok() {
+        if current.validate(self.category).is_ok() {
             return true;
         }
         self.next(current)
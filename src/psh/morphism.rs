@@ -0,0 +1,256 @@
+use super::Presheaf;
+use crate::cat::Category;
+use crate::set::{Backtrack, Set, Variable};
+
+#[derive(Debug, PartialEq)]
+pub enum PresheafMorphismError {
+    NotFibrePreserving { s: usize },
+    NotNatural { s: usize, f: usize },
+}
+
+impl std::fmt::Display for PresheafMorphismError {
+    fn fmt(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PresheafMorphismError::NotFibrePreserving { s } => {
+                write!(formatter, "Not fibre-preserving: s={}", s)
+            }
+            PresheafMorphismError::NotNatural { s, f } => {
+                write!(formatter, "Not natural: s={} and f={}", s, f)
+            }
+        }
+    }
+}
+
+impl std::error::Error for PresheafMorphismError {}
+
+// A morphism of presheaves P → Q, stored as the image of every section of P.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct PresheafMorphism {
+    map: Vec<usize>,
+}
+
+impl PresheafMorphism {
+    #[inline(always)]
+    pub fn new(map: Vec<usize>) -> Self {
+        PresheafMorphism { map }
+    }
+
+    #[inline(always)]
+    pub fn allocate(source: &Presheaf) -> Variable<Self> {
+        Variable::uninitialized(PresheafMorphism {
+            map: vec![0; source.number_of_sections()],
+        })
+    }
+
+    #[inline(always)]
+    pub fn identity(presheaf: &Presheaf) -> Self {
+        PresheafMorphism {
+            map: (0..presheaf.number_of_sections()).collect(),
+        }
+    }
+
+    #[inline(always)]
+    pub fn image(&self, section: usize) -> usize {
+        self.map[section]
+    }
+
+    // The composite of `self` after `first`.
+    #[inline(always)]
+    pub fn compose(&self, first: &PresheafMorphism) -> Self {
+        PresheafMorphism {
+            map: first.map.iter().map(|&s| self.image(s)).collect(),
+        }
+    }
+
+    #[inline(always)]
+    pub fn validate(
+        &self,
+        category: &Category,
+        source: &Presheaf,
+        target: &Presheaf,
+    ) -> Result<(), PresheafMorphismError> {
+        for s in 0..source.number_of_sections() {
+            self.validate_section(category, source, target, s)?;
+        }
+        Ok(())
+    }
+
+    // Checks that `s` is sent into the right fibre, and that φ(t·f) = φ(t)·f
+    // whenever t·f is defined and `s` is t or t·f, considering only
+    // sections up to `s`.
+    #[inline(always)]
+    fn validate_section(
+        &self,
+        category: &Category,
+        source: &Presheaf,
+        target: &Presheaf,
+        s: usize,
+    ) -> Result<(), PresheafMorphismError> {
+        if target.pi(self.image(s)) != source.pi(s) {
+            return Err(PresheafMorphismError::NotFibrePreserving { s });
+        }
+        for t in 0..=s {
+            for f in 0..category.number_of_morphisms() {
+                let Some(u) = source.action(t, f) else {
+                    continue;
+                };
+                if u > s || (t != s && u != s) {
+                    continue;
+                }
+                if Some(self.image(u)) != target.action(self.image(t), f) {
+                    return Err(PresheafMorphismError::NotNatural { s: t, f });
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+// Enumerates Hom(P, Q). Sections of P are mapped one at a time, each only
+// into the fibre of Q over its own object, and a partial map is abandoned as
+// soon as it fails to commute with the action on the sections mapped so far.
+#[derive(Clone, Debug)]
+pub struct PresheafMorphismSet<'a> {
+    category: &'a Category,
+    source: &'a Presheaf,
+    target: &'a Presheaf,
+    fibres: Vec<Vec<usize>>,
+}
+
+impl<'a> PresheafMorphismSet<'a> {
+    #[inline(always)]
+    pub fn new(
+        category: &'a Category,
+        source: &'a Presheaf,
+        target: &'a Presheaf,
+    ) -> Self {
+        let fibres = (0..category.number_of_objects())
+            .map(|object| target.fibre(object))
+            .collect();
+        PresheafMorphismSet {
+            category,
+            source,
+            target,
+            fibres,
+        }
+    }
+}
+
+// Slots are the sections of P, each running through the fibre of Q over its
+// object.
+impl Backtrack<PresheafMorphism> for PresheafMorphismSet<'_> {
+    #[inline(always)]
+    fn slots(&self, _: &PresheafMorphism) -> usize {
+        self.source.number_of_sections()
+    }
+
+    #[inline(always)]
+    fn candidates(&self, _: &PresheafMorphism, s: usize) -> usize {
+        self.fibres[self.source.pi(s)].len()
+    }
+
+    #[inline(always)]
+    fn assign(
+        &self,
+        current: &mut PresheafMorphism,
+        s: usize,
+        candidate: usize,
+    ) -> bool {
+        current.map[s] = self.fibres[self.source.pi(s)][candidate];
+        current
+            .validate_section(self.category, self.source, self.target, s)
+            .is_ok()
+    }
+
+    // The position of the current image of `s` within its fibre.
+    #[inline(always)]
+    fn candidate(&self, current: &PresheafMorphism, s: usize) -> usize {
+        let fibre = &self.fibres[self.source.pi(s)];
+        fibre.partition_point(|&t| t < current.map[s])
+    }
+}
+
+impl Set<PresheafMorphism> for PresheafMorphismSet<'_> {
+    #[inline(always)]
+    fn allocate(&self) -> Variable<PresheafMorphism> {
+        PresheafMorphism::allocate(self.source)
+    }
+
+    #[inline(always)]
+    fn next(&self, current: &mut PresheafMorphism) -> bool {
+        self.search_next(current)
+    }
+
+    #[inline(always)]
+    fn reset(&self, current: &mut PresheafMorphism) -> bool {
+        self.search_first(current)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cat::SizedCategoryClassSet;
+    use crate::cursor;
+    use crate::psh::SizedPresheafSet;
+    use crate::set::HomSet;
+
+    // Whether `map` sends every section into the right fibre and commutes
+    // with the action.
+    fn is_morphism(
+        category: &Category,
+        source: &Presheaf,
+        target: &Presheaf,
+        map: &[usize],
+    ) -> bool {
+        (0..source.number_of_sections()).all(|s| {
+            target.pi(map[s]) == source.pi(s)
+                && (0..category.number_of_morphisms()).all(|f| {
+                    source.action(s, f).is_none_or(|t| {
+                        target.action(map[s], f) == Some(map[t])
+                    })
+                })
+        })
+    }
+
+    #[test]
+    fn agrees_with_brute_force() {
+        for m in 1..=3 {
+            cursor!((_, category) in &SizedCategoryClassSet::new(m, None) => {
+                let mut presheaves = Vec::new();
+                for sections in 0..=3 {
+                    let set = SizedPresheafSet::new(category, sections);
+                    cursor!(presheaf in &set => {
+                        presheaves.push(presheaf.clone());
+                    });
+                }
+                for source in &presheaves {
+                    for target in &presheaves {
+                        let maps =
+                            HomSet::new(&source.sections(), &target.sections());
+                        let mut expected = 0;
+                        // HomSet always has a first map, even into nothing
+                        if target.number_of_sections() > 0
+                            || source.number_of_sections() == 0
+                        {
+                            cursor!(map in &maps => {
+                                if is_morphism(category, source, target, map) {
+                                    expected += 1;
+                                }
+                            });
+                        }
+                        let set =
+                            PresheafMorphismSet::new(category, source, target);
+                        let mut count = 0;
+                        cursor!(phi in &set => {
+                            let valid = phi.validate(category, source, target);
+                            assert!(valid.is_ok());
+                            count += 1;
+                        });
+                        assert_eq!(count, expected);
+                    }
+                }
+            });
+        }
+    }
+}
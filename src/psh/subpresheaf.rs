@@ -0,0 +1,337 @@
+use super::{Presheaf, PresheafMorphism};
+use crate::cat::Category;
+use crate::set::{Backtrack, Set, Variable};
+
+#[derive(Debug, PartialEq)]
+pub enum SubpresheafError {
+    NotClosed { s: usize, f: usize },
+}
+
+impl std::fmt::Display for SubpresheafError {
+    fn fmt(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SubpresheafError::NotClosed { s, f } => {
+                write!(formatter, "Not closed: s={} and f={}", s, f)
+            }
+        }
+    }
+}
+
+impl std::error::Error for SubpresheafError {}
+
+// A subpresheaf of a presheaf, stored as the membership of every section.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Subpresheaf {
+    members: Vec<bool>,
+}
+
+impl Subpresheaf {
+    #[inline(always)]
+    pub fn new(members: Vec<bool>) -> Self {
+        Subpresheaf { members }
+    }
+
+    #[inline(always)]
+    pub fn allocate(presheaf: &Presheaf) -> Variable<Self> {
+        Variable::uninitialized(Subpresheaf::bottom(presheaf))
+    }
+
+    #[inline(always)]
+    pub fn bottom(presheaf: &Presheaf) -> Self {
+        Subpresheaf {
+            members: vec![false; presheaf.number_of_sections()],
+        }
+    }
+
+    #[inline(always)]
+    pub fn top(presheaf: &Presheaf) -> Self {
+        Subpresheaf {
+            members: vec![true; presheaf.number_of_sections()],
+        }
+    }
+
+    #[inline(always)]
+    pub fn contains(&self, section: usize) -> bool {
+        self.members[section]
+    }
+
+    #[inline(always)]
+    pub fn is_contained_in(&self, other: &Subpresheaf) -> bool {
+        self.members
+            .iter()
+            .zip(&other.members)
+            .all(|(&a, &b)| !a || b)
+    }
+
+    #[inline(always)]
+    pub fn meet(&self, other: &Subpresheaf) -> Self {
+        Subpresheaf {
+            members: self
+                .members
+                .iter()
+                .zip(&other.members)
+                .map(|(&a, &b)| a && b)
+                .collect(),
+        }
+    }
+
+    #[inline(always)]
+    pub fn join(&self, other: &Subpresheaf) -> Self {
+        Subpresheaf {
+            members: self
+                .members
+                .iter()
+                .zip(&other.members)
+                .map(|(&a, &b)| a || b)
+                .collect(),
+        }
+    }
+
+    // The Heyting implication A ⇒ B: the sections s such that s·f ∈ B for
+    // every f with s·f ∈ A.
+    pub fn implication(
+        &self,
+        category: &Category,
+        presheaf: &Presheaf,
+        other: &Subpresheaf,
+    ) -> Self {
+        Subpresheaf {
+            members: (0..presheaf.number_of_sections())
+                .map(|s| {
+                    (0..category.number_of_morphisms()).all(|f| {
+                        presheaf.action(s, f).is_none_or(|t| {
+                            !self.contains(t) || other.contains(t)
+                        })
+                    })
+                })
+                .collect(),
+        }
+    }
+
+    // The negation ¬A = A ⇒ ⊥.
+    pub fn negation(&self, category: &Category, presheaf: &Presheaf) -> Self {
+        self.implication(category, presheaf, &Subpresheaf::bottom(presheaf))
+    }
+
+    // The pseudocomplement, the largest subpresheaf disjoint from A. In a
+    // Heyting algebra such as Sub(P) it coincides with the negation.
+    #[inline(always)]
+    pub fn pseudocomplement(
+        &self,
+        category: &Category,
+        presheaf: &Presheaf,
+    ) -> Self {
+        self.negation(category, presheaf)
+    }
+
+    #[inline(always)]
+    pub fn validate(
+        &self,
+        category: &Category,
+        presheaf: &Presheaf,
+    ) -> Result<(), SubpresheafError> {
+        for s in 0..presheaf.number_of_sections() {
+            if !self.contains(s) {
+                continue;
+            }
+            for f in 0..category.number_of_morphisms() {
+                if presheaf.action(s, f).is_some_and(|t| !self.contains(t)) {
+                    return Err(SubpresheafError::NotClosed { s, f });
+                }
+            }
+        }
+        Ok(())
+    }
+
+    // Checks closure under the action for the pairs t, t·f among the
+    // sections up to `s` that involve `s`.
+    #[inline(always)]
+    fn validate_section(
+        &self,
+        category: &Category,
+        presheaf: &Presheaf,
+        s: usize,
+    ) -> Result<(), SubpresheafError> {
+        for t in 0..=s {
+            for f in 0..category.number_of_morphisms() {
+                let Some(u) = presheaf.action(t, f) else {
+                    continue;
+                };
+                if u > s || (t != s && u != s) {
+                    continue;
+                }
+                if self.contains(t) && !self.contains(u) {
+                    return Err(SubpresheafError::NotClosed { s: t, f });
+                }
+            }
+        }
+        Ok(())
+    }
+
+    // The subpresheaf as a presheaf in its own right, with its inclusion.
+    #[inline(always)]
+    pub fn to_presheaf(
+        &self,
+        category: &Category,
+        presheaf: &Presheaf,
+    ) -> (Presheaf, PresheafMorphism) {
+        presheaf.restrict(category, &self.members)
+    }
+}
+
+// Enumerates all subpresheaves of a presheaf, starting from the empty one.
+// Sections are decided one at a time, and a partial choice is abandoned as
+// soon as a member has a restriction that was left out.
+#[derive(Clone, Debug)]
+pub struct SubpresheafSet<'a> {
+    category: &'a Category,
+    presheaf: &'a Presheaf,
+}
+
+impl<'a> SubpresheafSet<'a> {
+    #[inline(always)]
+    pub fn new(category: &'a Category, presheaf: &'a Presheaf) -> Self {
+        SubpresheafSet { category, presheaf }
+    }
+}
+
+// Slots are the sections, each either left out (0) or taken in (1).
+impl Backtrack<Subpresheaf> for SubpresheafSet<'_> {
+    #[inline(always)]
+    fn slots(&self, _: &Subpresheaf) -> usize {
+        self.presheaf.number_of_sections()
+    }
+
+    #[inline(always)]
+    fn candidates(&self, _: &Subpresheaf, _: usize) -> usize {
+        2
+    }
+
+    #[inline(always)]
+    fn assign(
+        &self,
+        current: &mut Subpresheaf,
+        s: usize,
+        candidate: usize,
+    ) -> bool {
+        current.members[s] = candidate == 1;
+        current
+            .validate_section(self.category, self.presheaf, s)
+            .is_ok()
+    }
+
+    #[inline(always)]
+    fn candidate(&self, current: &Subpresheaf, s: usize) -> usize {
+        usize::from(current.members[s])
+    }
+}
+
+impl Set<Subpresheaf> for SubpresheafSet<'_> {
+    #[inline(always)]
+    fn allocate(&self) -> Variable<Subpresheaf> {
+        Subpresheaf::allocate(self.presheaf)
+    }
+
+    #[inline(always)]
+    fn next(&self, current: &mut Subpresheaf) -> bool {
+        self.search_next(current)
+    }
+
+    #[inline(always)]
+    fn reset(&self, current: &mut Subpresheaf) -> bool {
+        self.search_first(current)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cat::SizedCategoryClassSet;
+    use crate::cursor;
+    use crate::psh::SizedPresheafSet;
+
+    // The presheaves with at most three sections on the categories with at
+    // most three morphisms, each with all its subpresheaves.
+    fn lattices() -> Vec<(Category, Presheaf, Vec<Subpresheaf>)> {
+        let mut lattices = Vec::new();
+        for m in 1..=3 {
+            cursor!((_, category) in &SizedCategoryClassSet::new(m, None) => {
+                for sections in 0..=3 {
+                    let set = SizedPresheafSet::new(category, sections);
+                    cursor!(presheaf in &set => {
+                        let mut subpresheaves = Vec::new();
+                        let subsets = SubpresheafSet::new(category, presheaf);
+                        cursor!(a in &subsets => {
+                            subpresheaves.push(a.clone());
+                        });
+                        lattices.push((
+                            category.clone(),
+                            presheaf.clone(),
+                            subpresheaves,
+                        ));
+                    });
+                }
+            });
+        }
+        lattices
+    }
+
+    #[test]
+    fn enumerates_the_subsets_closed_under_the_action() {
+        for (category, presheaf, subpresheaves) in lattices() {
+            let n = presheaf.number_of_sections();
+            let closed = (0..1usize << n)
+                .filter(|subset| {
+                    (0..n).all(|s| {
+                        subset & (1 << s) == 0
+                            || (0..category.number_of_morphisms()).all(|f| {
+                                presheaf
+                                    .action(s, f)
+                                    .is_none_or(|t| subset & (1 << t) != 0)
+                            })
+                    })
+                })
+                .count();
+            assert_eq!(subpresheaves.len(), closed);
+            for a in &subpresheaves {
+                assert!(a.validate(&category, &presheaf).is_ok());
+            }
+        }
+    }
+
+    // C ≤ (A ⇒ B) exactly when C ∧ A ≤ B.
+    #[test]
+    fn implication_is_right_adjoint_to_meet() {
+        for (category, presheaf, subpresheaves) in lattices() {
+            for a in &subpresheaves {
+                for b in &subpresheaves {
+                    let implication = a.implication(&category, &presheaf, b);
+                    assert!(implication.validate(&category, &presheaf).is_ok());
+                    for c in &subpresheaves {
+                        assert_eq!(
+                            c.is_contained_in(&implication),
+                            c.meet(a).is_contained_in(b)
+                        );
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn negation_is_the_largest_disjoint_subpresheaf() {
+        for (category, presheaf, subpresheaves) in lattices() {
+            let bottom = Subpresheaf::bottom(&presheaf);
+            for a in &subpresheaves {
+                let negation = a.negation(&category, &presheaf);
+                assert_eq!(negation, a.pseudocomplement(&category, &presheaf));
+                for c in &subpresheaves {
+                    assert_eq!(
+                        c.is_contained_in(&negation),
+                        c.meet(a) == bottom
+                    );
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,496 @@
+use super::{
+    Presheaf, PresheafMorphism, SubobjectClassifier, Subpresheaf,
+    SubpresheafError, SubpresheafSet,
+};
+use crate::cat::Category;
+use crate::set::{Filter, Set, Variable};
+use std::collections::HashMap;
+
+#[derive(Debug, PartialEq)]
+pub enum TopologyError {
+    MissingMaximalSieve { object: usize },
+    NotStable { sieve: usize, f: usize },
+    NotLocal { covering: usize, sieve: usize },
+}
+
+impl std::fmt::Display for TopologyError {
+    fn fmt(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TopologyError::MissingMaximalSieve { object } => {
+                write!(
+                    formatter,
+                    "Maximal sieve not covering: object={}",
+                    object
+                )
+            }
+            TopologyError::NotStable { sieve, f } => {
+                write!(formatter, "Not stable: sieve={} and f={}", sieve, f)
+            }
+            TopologyError::NotLocal { covering, sieve } => {
+                write!(
+                    formatter,
+                    "Not local: covering={} and sieve={}",
+                    covering, sieve
+                )
+            }
+        }
+    }
+}
+
+impl std::error::Error for TopologyError {}
+
+// A Grothendieck topology, stored as the subpresheaf J of Ω of covering
+// sieves. Sieves are referred to by their section of Ω.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct GrothendieckTopology {
+    covering: Subpresheaf,
+}
+
+impl GrothendieckTopology {
+    #[inline(always)]
+    pub fn new(covering: Subpresheaf) -> Self {
+        GrothendieckTopology { covering }
+    }
+
+    #[inline(always)]
+    pub fn allocate(classifier: &SubobjectClassifier) -> Variable<Self> {
+        Variable::uninitialized(GrothendieckTopology {
+            covering: Subpresheaf::bottom(&classifier.presheaf),
+        })
+    }
+
+    #[inline(always)]
+    pub fn covers(&self, sieve: usize) -> bool {
+        self.covering.contains(sieve)
+    }
+
+    #[inline(always)]
+    pub fn covering(&self) -> &Subpresheaf {
+        &self.covering
+    }
+
+    #[inline(always)]
+    pub fn validate(
+        &self,
+        category: &Category,
+        classifier: &SubobjectClassifier,
+    ) -> Result<(), TopologyError> {
+        self.validate_maximality(category, classifier)?;
+        self.validate_stability(category, classifier)?;
+        self.validate_locality(category, classifier)?;
+        Ok(())
+    }
+
+    #[inline(always)]
+    fn validate_maximality(
+        &self,
+        category: &Category,
+        classifier: &SubobjectClassifier,
+    ) -> Result<(), TopologyError> {
+        for object in 0..category.number_of_objects() {
+            if !self.covers(classifier.truth.image(object)) {
+                return Err(TopologyError::MissingMaximalSieve { object });
+            }
+        }
+        Ok(())
+    }
+
+    #[inline(always)]
+    fn validate_stability(
+        &self,
+        category: &Category,
+        classifier: &SubobjectClassifier,
+    ) -> Result<(), TopologyError> {
+        self.covering
+            .validate(category, &classifier.presheaf)
+            .map_err(|SubpresheafError::NotClosed { s, f }| {
+                TopologyError::NotStable { sieve: s, f }
+            })
+    }
+
+    // If S covers c and f*R covers for every f in S, then R covers c.
+    #[inline(always)]
+    fn validate_locality(
+        &self,
+        category: &Category,
+        classifier: &SubobjectClassifier,
+    ) -> Result<(), TopologyError> {
+        let omega = &classifier.presheaf;
+        for covering in 0..omega.number_of_sections() {
+            if !self.covers(covering) {
+                continue;
+            }
+            let object = omega.pi(covering);
+            let members = classifier.sieve(covering);
+            for sieve in omega.fibre(object) {
+                if self.covers(sieve) {
+                    continue;
+                }
+                let local = (0..category.number_of_morphisms()).all(|f| {
+                    !members[f]
+                        || omega
+                            .action(sieve, f)
+                            .is_some_and(|t| self.covers(t))
+                });
+                if local {
+                    return Err(TopologyError::NotLocal { covering, sieve });
+                }
+            }
+        }
+        Ok(())
+    }
+
+    // Whether every matching family for a covering sieve of `presheaf` has
+    // exactly one amalgamation.
+    pub fn is_sheaf(
+        &self,
+        category: &Category,
+        classifier: &SubobjectClassifier,
+        presheaf: &Presheaf,
+    ) -> bool {
+        let omega = &classifier.presheaf;
+        (0..omega.number_of_sections())
+            .filter(|&covering| self.covers(covering))
+            .all(|covering| {
+                let sieve = classifier.sieve(covering);
+                let families = matching_families(category, presheaf, sieve);
+                let fibre = presheaf.fibre(omega.pi(covering));
+                let mut restrictions: Vec<Vec<Option<usize>>> = fibre
+                    .iter()
+                    .map(|&s| restrict(category, presheaf, s, sieve))
+                    .collect();
+                restrictions.sort();
+                restrictions.dedup();
+                restrictions.len() == fibre.len()
+                    && families.len() == fibre.len()
+            })
+    }
+
+    // The plus construction P⁺ with its canonical map P → P⁺. A section of
+    // P⁺ over c is a class of matching families for covering sieves on c,
+    // two families being identified when they agree on a covering sieve.
+    pub fn plus(
+        &self,
+        category: &Category,
+        classifier: &SubobjectClassifier,
+        presheaf: &Presheaf,
+    ) -> (Presheaf, PresheafMorphism) {
+        let omega = &classifier.presheaf;
+
+        let mut elements: Vec<(usize, Vec<Option<usize>>)> = Vec::new();
+        for covering in 0..omega.number_of_sections() {
+            if !self.covers(covering) {
+                continue;
+            }
+            let sieve = classifier.sieve(covering);
+            for family in matching_families(category, presheaf, sieve) {
+                elements.push((covering, family));
+            }
+        }
+        let index: HashMap<&(usize, Vec<Option<usize>>), usize> = elements
+            .iter()
+            .enumerate()
+            .map(|(element, key)| (key, element))
+            .collect();
+
+        // number the classes in the order of their first element
+        let mut classes = vec![usize::MAX; elements.len()];
+        let mut representatives = Vec::new();
+        let mut pi = Vec::new();
+        for a in 0..elements.len() {
+            if classes[a] != usize::MAX {
+                continue;
+            }
+            let class = representatives.len();
+            let object = omega.pi(elements[a].0);
+            representatives.push(a);
+            pi.push(object);
+            for b in a..elements.len() {
+                if classes[b] == usize::MAX
+                    && omega.pi(elements[b].0) == object
+                    && self.agree(
+                        category,
+                        classifier,
+                        &elements[a],
+                        &elements[b],
+                    )
+                {
+                    classes[b] = class;
+                }
+            }
+        }
+
+        // (S, x)·f = (f*S, g ↦ x(f∘g))
+        let plus = Presheaf::from_fn(category, pi, |class, f| {
+            let (covering, family) = &elements[representatives[class]];
+            let pulled_back = omega.action(*covering, f)?;
+            let sieve = classifier.sieve(pulled_back);
+            let restricted = (0..category.number_of_morphisms())
+                .map(|g| {
+                    if sieve[g] {
+                        family[category.composition(f, g)?]
+                    } else {
+                        None
+                    }
+                })
+                .collect();
+            Some(classes[index[&(pulled_back, restricted)]])
+        });
+
+        let unit = PresheafMorphism::new(
+            (0..presheaf.number_of_sections())
+                .map(|s| {
+                    let maximal = classifier.truth.image(presheaf.pi(s));
+                    let sieve = classifier.sieve(maximal);
+                    let family = restrict(category, presheaf, s, sieve);
+                    classes[index[&(maximal, family)]]
+                })
+                .collect(),
+        );
+        (plus, unit)
+    }
+
+    // The sheafification P⁺⁺ with its unit P → P⁺⁺.
+    pub fn sheafification(
+        &self,
+        category: &Category,
+        classifier: &SubobjectClassifier,
+        presheaf: &Presheaf,
+    ) -> (Presheaf, PresheafMorphism) {
+        let (plus, first) = self.plus(category, classifier, presheaf);
+        let (sheaf, second) = self.plus(category, classifier, &plus);
+        (sheaf, second.compose(&first))
+    }
+
+    // Whether two matching families over the same object agree on a
+    // covering sieve, that is on the sieve of morphisms where both are
+    // defined and equal.
+    fn agree(
+        &self,
+        category: &Category,
+        classifier: &SubobjectClassifier,
+        left: &(usize, Vec<Option<usize>>),
+        right: &(usize, Vec<Option<usize>>),
+    ) -> bool {
+        let object = classifier.presheaf.pi(left.0);
+        let common: Vec<bool> = (0..category.number_of_morphisms())
+            .map(|f| left.1[f].is_some() && left.1[f] == right.1[f])
+            .collect();
+        self.covers(classifier.section(object, &common))
+    }
+}
+
+// The family f ↦ s·f on `sieve`.
+fn restrict(
+    category: &Category,
+    presheaf: &Presheaf,
+    s: usize,
+    sieve: &[bool],
+) -> Vec<Option<usize>> {
+    (0..category.number_of_morphisms())
+        .map(|f| sieve[f].then(|| presheaf.action(s, f)).flatten())
+        .collect()
+}
+
+// All matching families for `sieve`: choices of a section x(f) over the
+// source of every f in the sieve with x(f)·g = x(f∘g).
+fn matching_families(
+    category: &Category,
+    presheaf: &Presheaf,
+    sieve: &[bool],
+) -> Vec<Vec<Option<usize>>> {
+    let members: Vec<usize> = (0..category.number_of_morphisms())
+        .filter(|&f| sieve[f])
+        .collect();
+    let mut families = Vec::new();
+    let mut family = vec![None; category.number_of_morphisms()];
+    extend_families(
+        category,
+        presheaf,
+        &members,
+        0,
+        &mut family,
+        &mut families,
+    );
+    families
+}
+
+fn extend_families(
+    category: &Category,
+    presheaf: &Presheaf,
+    members: &[usize],
+    position: usize,
+    family: &mut Vec<Option<usize>>,
+    families: &mut Vec<Vec<Option<usize>>>,
+) {
+    if position == members.len() {
+        families.push(family.clone());
+        return;
+    }
+    let h = members[position];
+    for s in presheaf.fibre(category.source(h)) {
+        family[h] = Some(s);
+        let consistent = members[..=position].iter().all(|&f| {
+            (0..category.number_of_morphisms()).all(|g| {
+                let Some(fg) = category.composition(f, g) else {
+                    return true;
+                };
+                if f != h && fg != h {
+                    return true;
+                }
+                match (family[f], family[fg]) {
+                    (Some(x), Some(y)) => presheaf.action(x, g) == Some(y),
+                    _ => true,
+                }
+            })
+        });
+        if consistent {
+            extend_families(
+                category,
+                presheaf,
+                members,
+                position + 1,
+                family,
+                families,
+            );
+        }
+    }
+    family[h] = None;
+}
+
+// Enumerates all Grothendieck topologies on a category, by running through
+// the subpresheaves of Ω and keeping those that satisfy the axioms.
+#[derive(Clone, Debug)]
+pub struct GrothendieckTopologySet<'a> {
+    category: &'a Category,
+    classifier: &'a SubobjectClassifier,
+    subpresheaves: SubpresheafSet<'a>,
+}
+
+impl<'a> GrothendieckTopologySet<'a> {
+    #[inline(always)]
+    pub fn new(
+        category: &'a Category,
+        classifier: &'a SubobjectClassifier,
+    ) -> Self {
+        GrothendieckTopologySet {
+            category,
+            classifier,
+            subpresheaves: SubpresheafSet::new(category, &classifier.presheaf),
+        }
+    }
+}
+
+impl Filter<GrothendieckTopology> for GrothendieckTopologySet<'_> {
+    #[inline(always)]
+    fn inner_reset(&self, current: &mut GrothendieckTopology) -> bool {
+        self.subpresheaves.reset(&mut current.covering)
+    }
+
+    #[inline(always)]
+    fn inner_next(&self, current: &mut GrothendieckTopology) -> bool {
+        self.subpresheaves.next(&mut current.covering)
+    }
+
+    #[inline(always)]
+    fn accepts(&self, current: &GrothendieckTopology) -> bool {
+        current.validate(self.category, self.classifier).is_ok()
+    }
+}
+
+impl Set<GrothendieckTopology> for GrothendieckTopologySet<'_> {
+    #[inline(always)]
+    fn allocate(&self) -> Variable<GrothendieckTopology> {
+        GrothendieckTopology::allocate(self.classifier)
+    }
+
+    #[inline(always)]
+    fn next(&self, current: &mut GrothendieckTopology) -> bool {
+        self.filter_next(current)
+    }
+
+    #[inline(always)]
+    fn reset(&self, current: &mut GrothendieckTopology) -> bool {
+        self.filter_first(current)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cat::{CategoryClassSet, UNDEFINED};
+    use crate::cursor;
+    use crate::psh::PresheafSet;
+
+    fn monoids(n: usize) -> Vec<Category> {
+        let mut monoids = Vec::new();
+        let set = CategoryClassSet::new(1, vec![0; n - 1], vec![0; n - 1]);
+        cursor!(monoid in &set => {
+            monoids.push(monoid.clone());
+        });
+        monoids
+    }
+
+    fn topologies(
+        category: &Category,
+        classifier: &SubobjectClassifier,
+    ) -> Vec<GrothendieckTopology> {
+        let mut topologies = Vec::new();
+        let set = GrothendieckTopologySet::new(category, classifier);
+        cursor!(topology in &set => {
+            topologies.push(topology.clone());
+        });
+        topologies
+    }
+
+    #[test]
+    fn counts_topologies() {
+        // a group has only the trivial and the degenerate topology, the
+        // idempotent e also has the one where {e} covers
+        let mut counts: Vec<usize> = monoids(2)
+            .iter()
+            .map(|monoid| {
+                let classifier = Presheaf::subobject_classifier(monoid);
+                topologies(monoid, &classifier).len()
+            })
+            .collect();
+        counts.sort_unstable();
+        assert_eq!(counts, [2, 3]);
+
+        // 0 → 1, whose presheaf topos has four subtoposes
+        let arrow = Category::new(2, vec![0], vec![1], vec![UNDEFINED]);
+        let classifier = Presheaf::subobject_classifier(&arrow);
+        assert_eq!(topologies(&arrow, &classifier).len(), 4);
+    }
+
+    #[test]
+    fn sheafification_gives_sheaves() {
+        for n in 1..=3 {
+            for monoid in monoids(n) {
+                let classifier = Presheaf::subobject_classifier(&monoid);
+                for topology in topologies(&monoid, &classifier) {
+                    for m in 1..=2 {
+                        let pi = vec![0; m];
+                        let acts = PresheafSet::new(&monoid, &pi);
+                        cursor!(act in &acts => {
+                            let (sheaf, unit) = topology.sheafification(
+                                &monoid,
+                                &classifier,
+                                act,
+                            );
+                            assert!(sheaf.validate(&monoid).is_ok());
+                            assert!(unit
+                                .validate(&monoid, act, &sheaf)
+                                .is_ok());
+                            assert!(topology.is_sheaf(
+                                &monoid,
+                                &classifier,
+                                &sheaf
+                            ));
+                        });
+                    }
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,95 @@
+use super::{Presheaf, PresheafMorphismSet};
+use crate::cat::Category;
+use crate::cursor;
+use crate::set::Set;
+
+impl Presheaf {
+    // The representable presheaf y(object). Its sections are the morphisms
+    // into `object` in increasing order, lying over their sources, and the
+    // action is precomposition. The identity on `object` is section 0.
+    pub fn representable(category: &Category, object: usize) -> Presheaf {
+        let morphisms = morphisms_into(category, object);
+        let mut sections = vec![0; category.number_of_morphisms()];
+        for (section, &h) in morphisms.iter().enumerate() {
+            sections[h] = section;
+        }
+        let pi = morphisms.iter().map(|&h| category.source(h)).collect();
+        Presheaf::from_fn(category, pi, |s, f| {
+            Some(sections[category.composition(morphisms[s], f)?])
+        })
+    }
+
+    // Checks the Yoneda lemma for `self` at `object`: evaluation at the
+    // identity is a bijection from Hom(y(object), self) onto the fibre of
+    // `self` over `object`.
+    pub fn check_yoneda(&self, category: &Category, object: usize) -> bool {
+        let representable = Presheaf::representable(category, object);
+        let morphisms =
+            PresheafMorphismSet::new(category, &representable, self);
+        let fibre = self.fibre(object);
+        let mut hit = vec![false; self.number_of_sections()];
+        let mut count = 0;
+        let mut injective = true;
+        cursor!(phi in &morphisms => {
+            let s = phi.image(0);
+            injective = injective && !hit[s];
+            hit[s] = true;
+            count += 1;
+        });
+        injective && count == fibre.len()
+    }
+}
+
+// The morphisms with target `object` in increasing order, which number the
+// sections of y(object).
+pub(crate) fn morphisms_into(category: &Category, object: usize) -> Vec<usize> {
+    (0..category.number_of_morphisms())
+        .filter(|&h| category.target(h) == object)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cat::{CategoryClassSet, UNDEFINED};
+    use crate::psh::PresheafSet;
+
+    #[test]
+    fn yoneda_holds_for_monoid_acts() {
+        for n in 1..=3 {
+            let monoids =
+                CategoryClassSet::new(1, vec![0; n - 1], vec![0; n - 1]);
+            cursor!(monoid in &monoids => {
+                assert_eq!(
+                    Presheaf::representable(monoid, 0).number_of_sections(),
+                    n
+                );
+                for m in 1..=3 {
+                    let pi = vec![0; m];
+                    let acts = PresheafSet::new(monoid, &pi);
+                    cursor!(act in &acts => {
+                        assert!(act.check_yoneda(monoid, 0));
+                    });
+                }
+            });
+        }
+    }
+
+    #[test]
+    fn yoneda_holds_between_representables_of_an_arrow() {
+        // 0 → 1
+        let arrow = Category::new(2, vec![0], vec![1], vec![UNDEFINED]);
+        let sizes: Vec<usize> = (0..2)
+            .map(|object| {
+                Presheaf::representable(&arrow, object).number_of_sections()
+            })
+            .collect();
+        assert_eq!(sizes, [1, 2]);
+        for c in 0..2 {
+            let representable = Presheaf::representable(&arrow, c);
+            for d in 0..2 {
+                assert!(representable.check_yoneda(&arrow, d));
+            }
+        }
+    }
+}
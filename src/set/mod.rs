@@ -41,8 +41,106 @@ impl<T> Variable<T> {
 
 pub trait Set<T> {
     fn allocate(&self) -> Variable<T>;
-    fn reset<'a>(&self, current: &'a mut T) -> bool;
-    fn next<'a>(&self, current: &'a mut T) -> bool;
+    fn reset(&self, current: &mut T) -> bool;
+    fn next(&self, current: &mut T) -> bool;
+}
+
+// A search over the values of a fixed number of slots, each running through
+// its candidates in turn. A partial value is abandoned as soon as `assign`
+// rejects its last slot, so the search only visits consistent prefixes.
+// Sets built on it implement `reset` by `search_first` and `next` by
+// `search_next`.
+pub trait Backtrack<T> {
+    fn slots(&self, current: &T) -> usize;
+
+    // The number of candidates for `slot`, given the slots before it.
+    fn candidates(&self, current: &T, slot: usize) -> usize;
+
+    // Puts the `candidate`-th candidate into `slot`, and checks it against
+    // the slots before it.
+    fn assign(&self, current: &mut T, slot: usize, candidate: usize) -> bool;
+
+    // The position among the candidates of the value currently in `slot`.
+    fn candidate(&self, current: &T, slot: usize) -> usize;
+
+    #[inline(always)]
+    fn search_first(&self, current: &mut T) -> bool {
+        if self.slots(current) == 0 {
+            return true;
+        }
+        self.search_from(current, 0, 0)
+    }
+
+    #[inline(always)]
+    fn search_next(&self, current: &mut T) -> bool {
+        let slots = self.slots(current);
+        if slots == 0 {
+            return false;
+        }
+        let candidate = self.candidate(current, slots - 1) + 1;
+        self.search_from(current, slots - 1, candidate)
+    }
+
+    // Continues the search at `slot`, trying its candidates from `candidate`
+    // on; all slots before it hold a consistent partial value.
+    #[inline(always)]
+    fn search_from(
+        &self,
+        current: &mut T,
+        mut slot: usize,
+        mut candidate: usize,
+    ) -> bool {
+        let slots = self.slots(current);
+        loop {
+            if candidate < self.candidates(current, slot) {
+                if !self.assign(current, slot, candidate) {
+                    candidate += 1;
+                } else if slot + 1 == slots {
+                    return true;
+                } else {
+                    slot += 1;
+                    candidate = 0;
+                }
+            } else {
+                if slot == 0 {
+                    return false;
+                }
+                slot -= 1;
+                candidate = self.candidate(current, slot) + 1;
+            }
+        }
+    }
+}
+
+// The values of an inner set that pass a test, in the order of the inner
+// set. Sets built on it implement `reset` by `filter_first` and `next` by
+// `filter_next`.
+pub trait Filter<T> {
+    // `reset` of the inner set.
+    fn inner_reset(&self, current: &mut T) -> bool;
+
+    // `next` of the inner set.
+    fn inner_next(&self, current: &mut T) -> bool;
+
+    fn accepts(&self, current: &T) -> bool;
+
+    #[inline(always)]
+    fn filter_first(&self, current: &mut T) -> bool {
+        if !self.inner_reset(current) {
+            return false;
+        }
+        self.accepts(current) || self.filter_next(current)
+    }
+
+    #[inline(always)]
+    fn filter_next(&self, current: &mut T) -> bool {
+        while self.inner_next(current) {
+            if self.accepts(current) {
+                return true;
+            }
+        }
+        false
+    }
 }
 
 #[macro_export]
@@ -97,23 +195,15 @@ impl Set<usize> for AtomSet {
     }
 
     #[inline(always)]
-    fn next<'a>(&self, current: &'a mut usize) -> bool {
+    fn next(&self, current: &mut usize) -> bool {
         *current += 1;
-        if *current < self.size {
-            true
-        } else {
-            false
-        }
+        *current < self.size
     }
 
     #[inline(always)]
-    fn reset<'a>(&self, current: &'a mut usize) -> bool {
+    fn reset(&self, current: &mut usize) -> bool {
         *current = 0;
-        if *current < self.size {
-            true
-        } else {
-            false
-        }
+        *current < self.size
     }
 }
 
@@ -147,9 +237,9 @@ impl ProductSet {
     pub fn get(&self, value: &[usize]) -> usize {
         let mut index = 0;
         let mut multiplier = 1;
-        for i in 0..self.sizes.len() {
-            index += value[i] * multiplier;
-            multiplier *= self.sizes[i];
+        for (&x, &size) in value.iter().zip(&self.sizes) {
+            index += x * multiplier;
+            multiplier *= size;
         }
 
         index
@@ -163,23 +253,21 @@ impl Set<Vec<usize>> for ProductSet {
     }
 
     #[inline(always)]
-    fn next<'a>(&self, current: &'a mut Vec<usize>) -> bool {
-        for i in 0..self.sizes.len() {
-            current[i] += 1;
-            if current[i] < self.sizes[i] {
+    fn next(&self, current: &mut Vec<usize>) -> bool {
+        for (x, &size) in current.iter_mut().zip(&self.sizes) {
+            *x += 1;
+            if *x < size {
                 return true;
             } else {
-                current[i] = 0;
+                *x = 0;
             }
         }
         false
     }
 
     #[inline(always)]
-    fn reset<'a>(&self, current: &'a mut Vec<usize>) -> bool {
-        for i in 0..self.sizes.len() {
-            current[i] = 0;
-        }
+    fn reset(&self, current: &mut Vec<usize>) -> bool {
+        current.fill(0);
         true
     }
 }
@@ -226,23 +314,21 @@ impl Set<Vec<usize>> for HomSet {
     }
 
     #[inline(always)]
-    fn next<'a>(&self, current: &'a mut Vec<usize>) -> bool {
-        for i in 0..self.domain_size {
-            current[i] += 1;
-            if current[i] < self.target_size {
+    fn next(&self, current: &mut Vec<usize>) -> bool {
+        for x in current.iter_mut() {
+            *x += 1;
+            if *x < self.target_size {
                 return true;
             } else {
-                current[i] = 0;
+                *x = 0;
             }
         }
         false
     }
 
     #[inline(always)]
-    fn reset<'a>(&self, current: &'a mut Vec<usize>) -> bool {
-        for i in 0..self.domain_size {
-            current[i] = 0;
-        }
+    fn reset(&self, current: &mut Vec<usize>) -> bool {
+        current.fill(0);
         true
     }
 }
@@ -0,0 +1,858 @@
+use crate::cat::{
+    is_valid_name, Category, CategoryBuilder, CategoryBuilderError,
+    CategoryNames,
+};
+use crate::psh::{
+    Presheaf, PresheafBuilder, PresheafBuilderError, PresheafError,
+};
+use std::collections::HashSet;
+
+// A plain text format for a category and presheaves on it, one statement
+// per line:
+//
+//     category
+//       object A
+//       object B
+//       morphism f: A -> B
+//       morphism g: B -> A
+//       g∘f = id_A
+//       f∘g = id_B
+//
+//     presheaf
+//       section x: A
+//       section y: B
+//       x·g = y
+//       y·f = x
+//
+// The category comes first, followed by any number of presheaves. Names are
+// declared before they are used and consist of any characters other than
+// white space and `:`, `=`, `∘`, `·`, `→` and `#`; a name may not contain
+// `->` either. The builders reject other names, so every document can be
+// written. The identity on an object A is called id_A. A composition is
+// needed for every composable pair of non-identity morphisms, and an action
+// for every non-identity morphism f and section over its target. Everything
+// after `#` on a line is a comment, and indentation is ignored.
+
+#[derive(Debug, PartialEq)]
+pub enum ParseErrorKind {
+    Expected { expected: String },
+    Category(CategoryBuilderError),
+    Presheaf(PresheafBuilderError),
+}
+
+impl std::fmt::Display for ParseErrorKind {
+    fn fmt(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ParseErrorKind::Expected { expected } => {
+                write!(formatter, "Expected {}", expected)
+            }
+            ParseErrorKind::Category(error) => write!(formatter, "{}", error),
+            ParseErrorKind::Presheaf(error) => write!(formatter, "{}", error),
+        }
+    }
+}
+
+// An error with the line and column, both counted from 1, at which it was
+// found. Errors about a category or presheaf as a whole, such as a missing
+// composition, are reported at its header.
+#[derive(Debug, PartialEq)]
+pub struct ParseError {
+    pub line: usize,
+    pub column: usize,
+    pub kind: ParseErrorKind,
+}
+
+impl std::fmt::Display for ParseError {
+    fn fmt(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(formatter, "{}:{}: {}", self.line, self.column, self.kind)
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+// An error from adding to a document directly rather than by parsing.
+#[derive(Debug, PartialEq)]
+pub enum TextError {
+    WrongNumberOfObjects { expected: usize, found: usize },
+    WrongNumberOfMorphisms { expected: usize, found: usize },
+    WrongNumberOfSections { expected: usize, found: usize },
+    WrongCategory,
+    InvalidPresheaf(PresheafError),
+    Presheaf(PresheafBuilderError),
+}
+
+impl std::fmt::Display for TextError {
+    fn fmt(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TextError::WrongNumberOfObjects { expected, found } => {
+                write!(
+                    formatter,
+                    "Wrong number of object names: expected={} and found={}",
+                    expected, found
+                )
+            }
+            TextError::WrongNumberOfMorphisms { expected, found } => {
+                write!(
+                    formatter,
+                    "Wrong number of morphism names: expected={} and found={}",
+                    expected, found
+                )
+            }
+            TextError::WrongNumberOfSections { expected, found } => {
+                write!(
+                    formatter,
+                    "Wrong number of section names: expected={} and found={}",
+                    expected, found
+                )
+            }
+            TextError::WrongCategory => {
+                write!(formatter, "Presheaf on a different category")
+            }
+            TextError::InvalidPresheaf(error) => write!(formatter, "{}", error),
+            TextError::Presheaf(error) => write!(formatter, "{}", error),
+        }
+    }
+}
+
+impl std::error::Error for TextError {}
+
+// A category with presheaves on it, together with the names used for them
+// in the text format. All names are valid as for `CategoryBuilder`, so
+// writing a document with `Display` and parsing the result gives the same
+// document back.
+#[derive(Clone, Debug)]
+pub struct Document {
+    category: Category,
+    names: CategoryNames,
+    presheaves: Vec<(Presheaf, Vec<String>)>,
+}
+
+impl Document {
+    // A document without presheaves, for a category named by
+    // `CategoryBuilder` or `CategoryNames::indexed`. The names must be those
+    // of this category, one per object and one per morphism.
+    pub fn new(
+        category: Category,
+        names: CategoryNames,
+    ) -> Result<Self, TextError> {
+        if names.number_of_objects() != category.number_of_objects() {
+            return Err(TextError::WrongNumberOfObjects {
+                expected: category.number_of_objects(),
+                found: names.number_of_objects(),
+            });
+        }
+        if names.number_of_morphisms() != category.number_of_morphisms() {
+            return Err(TextError::WrongNumberOfMorphisms {
+                expected: category.number_of_morphisms(),
+                found: names.number_of_morphisms(),
+            });
+        }
+        Ok(Document {
+            category,
+            names,
+            presheaves: Vec::new(),
+        })
+    }
+
+    // Names the category as in `CategoryNames::indexed` and the sections of
+    // each presheaf s0, s1, ...
+    pub fn indexed(category: Category, presheaves: Vec<Presheaf>) -> Self {
+        let names = CategoryNames::indexed(&category);
+        let presheaves = presheaves
+            .into_iter()
+            .map(|presheaf| {
+                let sections = (0..presheaf.number_of_sections())
+                    .map(|s| format!("s{}", s))
+                    .collect();
+                (presheaf, sections)
+            })
+            .collect();
+        Document {
+            category,
+            names,
+            presheaves,
+        }
+    }
+
+    // Adds a presheaf on the category with the names of its sections, one
+    // per section. The presheaf must validate on the category.
+    pub fn push_presheaf(
+        &mut self,
+        presheaf: Presheaf,
+        sections: Vec<String>,
+    ) -> Result<(), TextError> {
+        let category = &self.category;
+        let n = category.number_of_objects();
+        let m = category.number_of_morphisms();
+        // the shape comes first, as `validate` indexes by it
+        if presheaf.number_of_objects() != n
+            || presheaf.number_of_morphisms() != m
+            || presheaf.pi.len() != presheaf.number_of_sections()
+            || presheaf.pi.iter().any(|&object| object >= n)
+            || presheaf.action.len() != presheaf.number_of_sections() * (m - n)
+        {
+            return Err(TextError::WrongCategory);
+        }
+        presheaf
+            .validate(category)
+            .map_err(TextError::InvalidPresheaf)?;
+        if sections.len() != presheaf.number_of_sections() {
+            return Err(TextError::WrongNumberOfSections {
+                expected: presheaf.number_of_sections(),
+                found: sections.len(),
+            });
+        }
+        let mut seen = HashSet::new();
+        for name in &sections {
+            if !is_valid_name(name) {
+                return Err(TextError::Presheaf(
+                    PresheafBuilderError::InvalidName { name: name.clone() },
+                ));
+            }
+            if !seen.insert(name) {
+                return Err(TextError::Presheaf(
+                    PresheafBuilderError::DuplicateName { name: name.clone() },
+                ));
+            }
+        }
+        self.presheaves.push((presheaf, sections));
+        Ok(())
+    }
+
+    #[inline(always)]
+    pub fn category(&self) -> &Category {
+        &self.category
+    }
+
+    #[inline(always)]
+    pub fn names(&self) -> &CategoryNames {
+        &self.names
+    }
+
+    // The presheaves with the names of their sections.
+    #[inline(always)]
+    pub fn presheaves(&self) -> &[(Presheaf, Vec<String>)] {
+        &self.presheaves
+    }
+}
+
+impl std::fmt::Display for Document {
+    fn fmt(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let category = &self.category;
+        let names = &self.names;
+        let n = category.number_of_objects();
+        let m = category.number_of_morphisms();
+
+        writeln!(formatter, "category")?;
+        for object in 0..n {
+            writeln!(formatter, "  object {}", names.object_name(object))?;
+        }
+        for f in n..m {
+            writeln!(
+                formatter,
+                "  morphism {}: {} -> {}",
+                names.morphism_name(f),
+                names.object_name(category.source(f)),
+                names.object_name(category.target(f))
+            )?;
+        }
+        for g in n..m {
+            for f in n..m {
+                if let Some(h) = category.composition(g, f) {
+                    writeln!(
+                        formatter,
+                        "  {}∘{} = {}",
+                        names.morphism_name(g),
+                        names.morphism_name(f),
+                        names.morphism_name(h)
+                    )?;
+                }
+            }
+        }
+
+        for (presheaf, sections) in &self.presheaves {
+            writeln!(formatter)?;
+            writeln!(formatter, "presheaf")?;
+            for (s, name) in sections.iter().enumerate() {
+                writeln!(
+                    formatter,
+                    "  section {}: {}",
+                    name,
+                    names.object_name(presheaf.pi(s))
+                )?;
+            }
+            for s in 0..presheaf.number_of_sections() {
+                for f in n..m {
+                    if let Some(t) = presheaf.action(s, f) {
+                        writeln!(
+                            formatter,
+                            "  {}·{} = {}",
+                            sections[s],
+                            names.morphism_name(f),
+                            sections[t]
+                        )?;
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+#[derive(Clone, Debug)]
+struct Token<'a> {
+    text: &'a str,
+    column: usize,
+}
+
+const SYMBOLS: [&str; 5] = [":", "=", "∘", "·", "->"];
+
+// Splits a line into names and symbols, dropping any comment. Columns count
+// characters, and → is read as ->.
+fn tokenize(line: &str) -> Vec<Token<'_>> {
+    let line = line.split_once('#').map_or(line, |(line, _)| line);
+    let chars: Vec<(usize, char)> = line.char_indices().collect();
+    let ends_name = |(index, c): (usize, char)| {
+        c.is_whitespace()
+            || matches!(c, ':' | '=' | '∘' | '·' | '→')
+            || line[index..].starts_with("->")
+    };
+    let mut tokens = Vec::new();
+    let mut i = 0;
+    while i < chars.len() {
+        let (start, c) = chars[i];
+        let column = i + 1;
+        if c.is_whitespace() {
+            i += 1;
+        } else if c == '→' {
+            tokens.push(Token { text: "->", column });
+            i += 1;
+        } else if line[start..].starts_with("->") {
+            tokens.push(Token { text: "->", column });
+            i += 2;
+        } else if ends_name((start, c)) {
+            let text = &line[start..start + c.len_utf8()];
+            tokens.push(Token { text, column });
+            i += 1;
+        } else {
+            let mut j = i + 1;
+            while j < chars.len() && !ends_name(chars[j]) {
+                j += 1;
+            }
+            let end = chars.get(j).map_or(line.len(), |&(end, _)| end);
+            tokens.push(Token {
+                text: &line[start..end],
+                column,
+            });
+            i = j;
+        }
+    }
+    tokens
+}
+
+// A non-empty line with its number, counted from 1, and its tokens.
+struct Statement<'a> {
+    line: usize,
+    tokens: Vec<Token<'a>>,
+}
+
+impl<'a> Statement<'a> {
+    // Checks the tokens against a pattern in which `_` stands for a name,
+    // and returns the names.
+    fn read(&self, pattern: &[&str]) -> Result<Vec<&'a str>, ParseError> {
+        let mut names = Vec::new();
+        for (i, &expected) in pattern.iter().enumerate() {
+            let found = self.tokens.get(i);
+            let matches = match found {
+                Some(token) if expected == "_" => {
+                    !SYMBOLS.contains(&token.text)
+                }
+                Some(token) => token.text == expected,
+                None => false,
+            };
+            if !matches {
+                let expected = if expected == "_" {
+                    "a name".to_string()
+                } else {
+                    format!("`{}`", expected)
+                };
+                return Err(self.expected(i, expected));
+            }
+            if expected == "_" {
+                names.push(found.map_or("", |token| token.text));
+            }
+        }
+        if pattern.len() < self.tokens.len() {
+            return Err(self.expected(pattern.len(), "the end of the line"));
+        }
+        Ok(names)
+    }
+
+    // An error for the token at `position`, or for the end of the line if
+    // there is none.
+    fn expected(&self, position: usize, expected: impl ToString) -> ParseError {
+        let column = match self.tokens.get(position) {
+            Some(token) => token.column,
+            None => self
+                .tokens
+                .last()
+                .map_or(1, |token| token.column + token.text.chars().count()),
+        };
+        ParseError {
+            line: self.line,
+            column,
+            kind: ParseErrorKind::Expected {
+                expected: expected.to_string(),
+            },
+        }
+    }
+
+    // The position of the token that an error from this statement, read
+    // with `pattern`, is about: the name it declares after its keyword, or
+    // the first use of an unknown name. The builders look names up in the
+    // order they appear, so the first use is the one that failed.
+    fn position(&self, pattern: &[&str], subject: &Subject) -> Option<usize> {
+        let declares = pattern[0] != "_";
+        let mut names = (0..pattern.len()).filter(|&i| pattern[i] == "_");
+        match subject {
+            Subject::Declared => names.next().filter(|_| declares),
+            Subject::Used(name) => names
+                .skip(usize::from(declares))
+                .find(|&i| self.tokens[i].text == name),
+        }
+    }
+
+    // An error at the token at `position`, or at the start of the statement.
+    fn error(
+        &self,
+        position: Option<usize>,
+        kind: ParseErrorKind,
+    ) -> ParseError {
+        let column = position
+            .and_then(|position| self.tokens.get(position))
+            .or(self.tokens.first())
+            .map_or(1, |token| token.column);
+        ParseError {
+            line: self.line,
+            column,
+            kind,
+        }
+    }
+
+    fn is_equation(&self, symbol: &str) -> bool {
+        self.tokens.get(1).is_some_and(|token| token.text == symbol)
+    }
+
+    fn is_header(&self) -> bool {
+        self.tokens.len() == 1 && self.tokens[0].text == "presheaf"
+    }
+}
+
+pub fn parse(input: &str) -> Result<Document, ParseError> {
+    let number_of_lines = input.lines().count();
+    let mut statements = input
+        .lines()
+        .enumerate()
+        .map(|(line, text)| Statement {
+            line: line + 1,
+            tokens: tokenize(text),
+        })
+        .filter(|statement| !statement.tokens.is_empty())
+        .peekable();
+    let end = Statement {
+        line: number_of_lines + 1,
+        tokens: Vec::new(),
+    };
+
+    let header = statements.next().unwrap_or(end);
+    header.read(&["category"])?;
+    let mut builder = CategoryBuilder::new();
+    let mut entries = Vec::new();
+    while let Some(statement) =
+        statements.next_if(|statement| !statement.is_header())
+    {
+        let pattern: &[&str] = if statement.is_equation("∘") {
+            let pattern = &["_", "∘", "_", "=", "_"];
+            let names = statement.read(pattern)?;
+            builder
+                .equation(&format!("{}∘{} = {}", names[0], names[1], names[2]));
+            pattern
+        } else if statement.tokens[0].text == "object" {
+            let pattern = &["object", "_"];
+            let names = statement.read(pattern)?;
+            builder.object(names[0]);
+            pattern
+        } else if statement.tokens[0].text == "morphism" {
+            let pattern = &["morphism", "_", ":", "_", "->", "_"];
+            let names = statement.read(pattern)?;
+            builder.morphism(names[0], names[1], names[2]);
+            pattern
+        } else {
+            return Err(statement.expected(
+                0,
+                "`object`, `morphism`, an equation or `presheaf`",
+            ));
+        };
+        entries.push((statement, pattern));
+    }
+    let (category, names) =
+        builder.build_located().map_err(|(entry, error)| {
+            let subject = category_error_subject(&error);
+            let kind = ParseErrorKind::Category(error);
+            match entry {
+                Some(entry) => {
+                    let (statement, pattern) = &entries[entry];
+                    let position = subject.and_then(|subject| {
+                        statement.position(pattern, &subject)
+                    });
+                    statement.error(position, kind)
+                }
+                None => header.error(None, kind),
+            }
+        })?;
+
+    let mut presheaves = Vec::new();
+    while let Some(header) = statements.next() {
+        header.read(&["presheaf"])?;
+        let mut builder = PresheafBuilder::new(&category, &names);
+        let mut entries = Vec::new();
+        while let Some(statement) =
+            statements.next_if(|statement| !statement.is_header())
+        {
+            let pattern: &[&str] = if statement.is_equation("·") {
+                let pattern = &["_", "·", "_", "=", "_"];
+                let names = statement.read(pattern)?;
+                builder.action(&format!(
+                    "{}·{} = {}",
+                    names[0], names[1], names[2]
+                ));
+                pattern
+            } else if statement.tokens[0].text == "section" {
+                let pattern = &["section", "_", ":", "_"];
+                let names = statement.read(pattern)?;
+                builder.section(names[0], names[1]);
+                pattern
+            } else {
+                return Err(
+                    statement.expected(0, "`section`, an action or `presheaf`")
+                );
+            };
+            entries.push((statement, pattern));
+        }
+        presheaves.push(builder.build_located().map_err(
+            |(entry, error)| {
+                let subject = presheaf_error_subject(&error);
+                let kind = ParseErrorKind::Presheaf(error);
+                match entry {
+                    Some(entry) => {
+                        let (statement, pattern) = &entries[entry];
+                        let position = subject.and_then(|subject| {
+                            statement.position(pattern, &subject)
+                        });
+                        statement.error(position, kind)
+                    }
+                    None => header.error(None, kind),
+                }
+            },
+        )?);
+    }
+
+    Ok(Document {
+        category,
+        names,
+        presheaves,
+    })
+}
+
+// What an error from a single statement is about: the name the statement
+// declares, or a name it uses.
+enum Subject {
+    Declared,
+    Used(String),
+}
+
+fn category_error_subject(error: &CategoryBuilderError) -> Option<Subject> {
+    match error {
+        CategoryBuilderError::InvalidName { .. }
+        | CategoryBuilderError::DuplicateName { .. } => Some(Subject::Declared),
+        CategoryBuilderError::UnknownObject { name }
+        | CategoryBuilderError::UnknownMorphism { name } => {
+            Some(Subject::Used(name.clone()))
+        }
+        _ => None,
+    }
+}
+
+fn presheaf_error_subject(error: &PresheafBuilderError) -> Option<Subject> {
+    match error {
+        PresheafBuilderError::InvalidName { .. }
+        | PresheafBuilderError::DuplicateName { .. } => Some(Subject::Declared),
+        PresheafBuilderError::UnknownObject { name }
+        | PresheafBuilderError::UnknownSection { name }
+        | PresheafBuilderError::UnknownMorphism { name } => {
+            Some(Subject::Used(name.clone()))
+        }
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cat::{SizedCategoryClassSet, UNDEFINED};
+    use crate::cursor;
+    use crate::psh::SizedPresheafSet;
+    use crate::set::Set;
+
+    const ISOMORPHISM: &str = "\
+# the walking isomorphism
+category
+  object A
+  object B
+  morphism f: A → B
+  morphism g:B->A
+  g∘f = id_A  # a comment
+  f ∘ g=id_B
+
+presheaf
+  section x: A
+  section y: B
+  x·g = y
+  y·f = x
+";
+
+    fn assert_same(left: &Document, right: &Document) {
+        assert_eq!(left.category(), right.category());
+        assert_eq!(left.names(), right.names());
+        assert_eq!(left.presheaves().len(), right.presheaves().len());
+        for ((p, p_names), (q, q_names)) in
+            left.presheaves().iter().zip(right.presheaves())
+        {
+            assert_eq!(p.pi, q.pi);
+            assert_eq!(p.action, q.action);
+            assert_eq!(p_names, q_names);
+        }
+    }
+
+    fn assert_round_trip(document: &Document) {
+        let text = document.to_string();
+        let parsed = parse(&text).unwrap();
+        assert_same(&parsed, document);
+        assert_eq!(parsed.to_string(), text);
+    }
+
+    fn error(input: &str) -> (usize, usize, ParseErrorKind) {
+        let error = parse(input).unwrap_err();
+        (error.line, error.column, error.kind)
+    }
+
+    #[test]
+    fn round_trip_named() {
+        let document = parse(ISOMORPHISM).unwrap();
+        assert_eq!(document.category().number_of_morphisms(), 4);
+        assert_eq!(document.names().morphism("g"), Some(3));
+        assert_round_trip(&document);
+    }
+
+    #[test]
+    fn round_trip_indexed() {
+        for m in 1..=4 {
+            let categories = SizedCategoryClassSet::new(m, None);
+            cursor!((_, category) in &categories => {
+                let mut presheaves = Vec::new();
+                for sections in 0..=2 {
+                    let set = SizedPresheafSet::new(category, sections);
+                    cursor!(presheaf in &set => {
+                        presheaves.push(presheaf.clone());
+                    });
+                }
+                presheaves.truncate(8);
+                assert_round_trip(&Document::indexed(
+                    category.clone(),
+                    presheaves,
+                ));
+            });
+        }
+    }
+
+    #[test]
+    fn empty_input() {
+        assert_eq!(
+            error(""),
+            (
+                1,
+                1,
+                ParseErrorKind::Expected {
+                    expected: "`category`".to_string()
+                }
+            )
+        );
+    }
+
+    #[test]
+    fn unknown_object() {
+        let input = "category\n  object A\n  morphism f: A -> B\n";
+        assert_eq!(
+            error(input),
+            (
+                3,
+                20,
+                ParseErrorKind::Category(CategoryBuilderError::UnknownObject {
+                    name: "B".to_string()
+                })
+            )
+        );
+    }
+
+    #[test]
+    fn unknown_object_named_like_the_morphism() {
+        // the unknown B is the target, not the morphism declared before it
+        let input = "category\n  object A\n  morphism B: A -> B\n";
+        assert_eq!(
+            error(input),
+            (
+                3,
+                20,
+                ParseErrorKind::Category(CategoryBuilderError::UnknownObject {
+                    name: "B".to_string()
+                })
+            )
+        );
+    }
+
+    #[test]
+    fn duplicate_name() {
+        let input = "category\n  object A\n  object B\n  object A\n";
+        assert_eq!(
+            error(input),
+            (
+                4,
+                10,
+                ParseErrorKind::Category(CategoryBuilderError::DuplicateName {
+                    name: "A".to_string()
+                })
+            )
+        );
+    }
+
+    #[test]
+    fn conflicting_composition() {
+        let input = "category\n  object A\n  morphism e: A -> A\n  \
+                     e∘e = e\n  e∘e = id_A\n";
+        assert_eq!(
+            error(input),
+            (
+                5,
+                3,
+                ParseErrorKind::Category(
+                    CategoryBuilderError::ConflictingComposition {
+                        g: "e".to_string(),
+                        f: "e".to_string()
+                    }
+                )
+            )
+        );
+    }
+
+    #[test]
+    fn missing_composition() {
+        let input = "# a comment\n  category\n  object A\n  \
+                     morphism e: A -> A\n";
+        assert_eq!(
+            error(input),
+            (
+                2,
+                3,
+                ParseErrorKind::Category(
+                    CategoryBuilderError::MissingComposition {
+                        g: "e".to_string(),
+                        f: "e".to_string()
+                    }
+                )
+            )
+        );
+    }
+
+    #[test]
+    fn invalid_names() {
+        let mut builder = CategoryBuilder::new();
+        builder.object("my obj");
+        assert_eq!(
+            builder.build().unwrap_err(),
+            CategoryBuilderError::InvalidName {
+                name: "my obj".to_string()
+            }
+        );
+
+        let mut builder = CategoryBuilder::new();
+        builder.object("A").morphism("f:1", "A", "A");
+        assert_eq!(
+            builder.build().unwrap_err(),
+            CategoryBuilderError::InvalidName {
+                name: "f:1".to_string()
+            }
+        );
+
+        let (category, names) =
+            CategoryBuilder::new().object("A").build().unwrap();
+        let mut document = Document::new(category, names).unwrap();
+        let presheaf = Presheaf::new(document.category(), vec![0], vec![]);
+        assert_eq!(
+            document.push_presheaf(presheaf.clone(), vec!["x->y".to_string()]),
+            Err(TextError::Presheaf(PresheafBuilderError::InvalidName {
+                name: "x->y".to_string()
+            }))
+        );
+        assert_eq!(
+            document.push_presheaf(presheaf, vec![]),
+            Err(TextError::WrongNumberOfSections {
+                expected: 1,
+                found: 0
+            })
+        );
+    }
+
+    #[test]
+    fn rejects_presheaves_on_another_category() {
+        let (point, point_names) =
+            CategoryBuilder::new().object("A").build().unwrap();
+        let (pair, pair_names) = CategoryBuilder::new()
+            .object("A")
+            .object("B")
+            .build()
+            .unwrap();
+        assert_eq!(
+            Document::new(point.clone(), pair_names).unwrap_err(),
+            TextError::WrongNumberOfObjects {
+                expected: 1,
+                found: 2
+            }
+        );
+
+        let mut document = Document::new(point, point_names).unwrap();
+        let presheaf = Presheaf::new(&pair, vec![1], vec![]);
+        assert_eq!(
+            document.push_presheaf(presheaf, vec!["x".to_string()]),
+            Err(TextError::WrongCategory)
+        );
+
+        let (arrow, arrow_names) = CategoryBuilder::new()
+            .object("A")
+            .object("B")
+            .morphism("f", "A", "B")
+            .build()
+            .unwrap();
+        let mut document = Document::new(arrow, arrow_names).unwrap();
+        // x lies over B, but x·f is undefined
+        let presheaf =
+            Presheaf::new(document.category(), vec![1], vec![UNDEFINED]);
+        assert_eq!(
+            document.push_presheaf(presheaf, vec!["x".to_string()]),
+            Err(TextError::InvalidPresheaf(PresheafError::NotWellDefined {
+                s: 0,
+                f: 2
+            }))
+        );
+        assert!(document.presheaves().is_empty());
+    }
+}